@@ -1,22 +1,258 @@
-use md5;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::process;
+use std::time::Instant;
 
-const HASH_SECRET: &str = "ckczppom";
-const NUM_CAP: i32 = 10_000_000;
+mod md5;
+mod miner;
+mod nonce;
+use miner::predicates::{contains_triple, has_leading_zeros, hex_pattern, leading_zeros};
+use miner::Miner;
+
+const DEFAULT_DIFFICULTIES: [usize; 2] = [5, 6];
+const NUM_CAP: u32 = 10_000_000;
+const PROGRESS_INTERVAL: u32 = 1_000_000;
+
+#[derive(Debug, PartialEq)]
+enum SearchError {
+    NotFound { zeros: usize, cap: u32 },
+}
+
+impl Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchError::NotFound { zeros, cap } => {
+                write!(f, "No hash with {zeros} leading zeros found below {cap}")
+            }
+        }
+    }
+}
+
+// Returns the first nonce for each of the requested difficulties, in the same order.
+// The search stops as soon as every difficulty has been satisfied.
+fn find_nonces(secret: &str, difficulties: &[usize], cap: u32) -> Result<Vec<u32>, SearchError> {
+    let mut nonces = vec![None; difficulties.len()];
+    let started = Instant::now();
+
+    // Mining is done in chunks so progress can be reported between them
+    let mut chunk_start = 1;
+    while chunk_start < cap {
+        let chunk_end = chunk_start.saturating_add(PROGRESS_INTERVAL).min(cap);
+
+        // Every remaining difficulty also satisfies the easiest one
+        let Some(easiest) = nonces
+            .iter()
+            .zip(difficulties)
+            .filter(|(nonce, _)| nonce.is_none())
+            .map(|(_, zeros)| *zeros)
+            .min()
+        else {
+            break;
+        };
+
+        for found in Miner::new(secret, chunk_start..chunk_end, leading_zeros(easiest)) {
+            for (nonce, zeros) in nonces.iter_mut().zip(difficulties) {
+                if nonce.is_none() && has_leading_zeros(&found.digest, *zeros) {
+                    *nonce = Some(found.nonce);
+                }
+            }
+
+            if nonces.iter().all(Option::is_some) {
+                break;
+            }
+        }
+
+        if chunk_end - chunk_start == PROGRESS_INTERVAL && nonces.iter().any(Option::is_none) {
+            report_progress(chunk_end - 1, cap, started);
+        }
+        chunk_start = chunk_end;
+    }
+
+    nonces
+        .into_iter()
+        .zip(difficulties)
+        .map(|(nonce, zeros)| nonce.ok_or(SearchError::NotFound { zeros: *zeros, cap }))
+        .collect()
+}
+
+fn report_progress(checked: u32, cap: u32, started: Instant) {
+    let elapsed = started.elapsed().as_secs_f64();
+    let rate = checked as f64 / elapsed;
+    let percent = checked as f64 / cap as f64 * 100.0;
+    eprintln!("Checked {checked} nonces ({percent:.1}% of cap), {rate:.0} hashes/s");
+}
+
+// Prints the first nonce matching a hex pattern or containing a triple digit.
+fn mine_first<P: Fn(&md5::Digest) -> bool>(secret: &str, predicate: P) {
+    match Miner::new(secret, 1..NUM_CAP, predicate).next() {
+        Some(found) => {
+            let hex: String = found
+                .digest
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect();
+            println!("{}: {hex}", found.nonce);
+        }
+        None => {
+            eprintln!("No matching hash found below {NUM_CAP}");
+            process::exit(1);
+        }
+    }
+}
+
+//The secret is the first argument, unless it is missing or a flag
+fn split_secret(mut args: Vec<String>) -> (Option<String>, Vec<String>) {
+    match args.first() {
+        Some(first) if !first.starts_with("--") => {
+            let secret = args.remove(0);
+            (Some(secret), args)
+        }
+        _ => (None, args),
+    }
+}
+
+// Usage: solution_4 [SECRET] [DIFFICULTY...]
+//        solution_4 [SECRET] --pattern HEX
+//        solution_4 [SECRET] --triple
+// The secret falls back to the contents of input.txt when not given.
 fn main() {
-    let mut i = 1;
-    while i < NUM_CAP {
-        let plaintext = String::from(HASH_SECRET) + &i.to_string();
-        // Returns e.g. dc 94 5c 58 4c be 97 b6 97 7e 88 3d 9d a5 ae 18
-        let hash = md5::compute(plaintext);
-        let segs = &hash[0..3];
-
-        if segs[0] == 0 && segs[1] == 0 && segs[2] < 16 {
-            if segs[2] == 0 {
-                println!("Six zeros: {i}");
-            } else if segs[2] < 16 {
-                println!("Five zeros: {i}");
+    let (secret, args) = split_secret(env::args().skip(1).collect());
+
+    let secret = match secret {
+        Some(secret) => secret,
+        None => {
+            let file_path = "input.txt";
+            match fs::read_to_string(file_path) {
+                Ok(secret) => secret.trim().to_string(),
+                Err(e) => panic!("Error reading file at {file_path}. Error {e}"),
+            }
+        }
+    };
+
+    match args.first().map(String::as_str) {
+        Some("--pattern") => {
+            let Some(pattern) = args.get(1) else {
+                panic!("--pattern expects a hex pattern such as 00?f");
+            };
+            match hex_pattern(pattern) {
+                Ok(predicate) => mine_first(&secret, predicate),
+                Err(e) => panic!("Invalid pattern {pattern}. Error {e}"),
             }
+            return;
+        }
+        Some("--triple") => {
+            mine_first(&secret, contains_triple);
+            return;
+        }
+        _ => {}
+    }
+
+    let mut difficulties: Vec<usize> = args
+        .iter()
+        .map(|arg| match arg.parse() {
+            Ok(zeros) => zeros,
+            Err(e) => panic!("Invalid difficulty {arg}. Error {e}"),
+        })
+        .collect();
+    if difficulties.is_empty() {
+        difficulties = DEFAULT_DIFFICULTIES.to_vec();
+    }
+
+    let nonces = match find_nonces(&secret, &difficulties, NUM_CAP) {
+        Ok(nonces) => nonces,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    for (zeros, nonce) in difficulties.iter().zip(nonces) {
+        println!("{zeros} zeros: {nonce}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nonce::NonceBuffer;
+
+    #[test]
+    fn flags_are_not_taken_as_the_secret() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect();
+
+        assert_eq!(
+            (Some(String::from("abcdef")), args(&["--triple"])),
+            split_secret(args(&["abcdef", "--triple"]))
+        );
+        assert_eq!(
+            (None, args(&["--triple"])),
+            split_secret(args(&["--triple"]))
+        );
+        assert_eq!(
+            (None, args(&["--pattern", "00"])),
+            split_secret(args(&["--pattern", "00"]))
+        );
+        assert_eq!((None, vec![]), split_secret(vec![]));
+    }
+
+    #[test]
+    fn finds_first_nonce_per_difficulty() {
+        // abcdef609043 hashes to 000001dbbfa...
+        assert_eq!(Ok(vec![1, 609043]), find_nonces("abcdef", &[0, 5], 700_000));
+    }
+
+    #[test]
+    fn buffered_hash_matches_plain_hash() {
+        let mut secret_state = md5::Context::new();
+        secret_state.update("abcdef");
+        let mut buffer = NonceBuffer::new(609040);
+
+        for i in 609040..609050u32 {
+            let mut state = secret_state.clone();
+            state.update(buffer.as_bytes());
+            assert_eq!(md5::compute(format!("abcdef{i}")), state.finalize());
+            buffer.increment();
+        }
+    }
+
+    #[test]
+    fn cap_reached_is_an_error() {
+        assert_eq!(
+            Err(SearchError::NotFound {
+                zeros: 5,
+                cap: 1000
+            }),
+            find_nonces("abcdef", &[5], 1000)
+        );
+    }
+
+    // Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_hashing_throughput() {
+        const ITERATIONS: u32 = 2_000_000;
+
+        let started = Instant::now();
+        for i in 1..=ITERATIONS {
+            let plaintext = String::from("ckczppom") + &i.to_string();
+            std::hint::black_box(md5::compute(plaintext));
+        }
+        let allocating = ITERATIONS as f64 / started.elapsed().as_secs_f64();
+
+        let started = Instant::now();
+        let mut secret_state = md5::Context::new();
+        secret_state.update("ckczppom");
+        let mut buffer = NonceBuffer::new(1);
+        for _ in 1..=ITERATIONS {
+            let mut state = secret_state.clone();
+            state.update(buffer.as_bytes());
+            std::hint::black_box(state.finalize());
+            buffer.increment();
         }
-        i += 1;
+        let buffered = ITERATIONS as f64 / started.elapsed().as_secs_f64();
+
+        println!("allocating: {allocating:.0} hashes/s");
+        println!("buffered:   {buffered:.0} hashes/s");
     }
 }
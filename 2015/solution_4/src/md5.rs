@@ -0,0 +1,226 @@
+// MD5 as described in RFC 1321.
+// The context is cheap to clone, so a shared prefix can be absorbed once and reused.
+
+pub type Digest = [u8; 16];
+
+const BLOCK_SIZE: usize = 64;
+
+const INITIAL_STATE: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+// Per-step left rotation amounts
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, //
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, //
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, //
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+// floor(abs(sin(i + 1)) * 2^32)
+const SINES: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+#[derive(Clone)]
+pub struct Context {
+    state: [u32; 4],
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+    length: u64,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        let mut data = data.as_ref();
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        // Top up a partially filled block first
+        if self.buffered > 0 {
+            let taken = data.len().min(BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + taken].copy_from_slice(&data[..taken]);
+            self.buffered += taken;
+            data = &data[taken..];
+
+            if self.buffered < BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            process_block(&mut self.state, &block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in &mut blocks {
+            process_block(&mut self.state, block.try_into().unwrap());
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    pub fn finalize(mut self) -> Digest {
+        let bit_length = self.length.wrapping_mul(8);
+
+        // A single 0x80 byte, zeros up to 56 mod 64, then the message length in bits
+        let padding_length = if self.buffered < 56 {
+            56 - self.buffered
+        } else {
+            BLOCK_SIZE + 56 - self.buffered
+        };
+        let mut padding = [0u8; BLOCK_SIZE];
+        padding[0] = 0x80;
+        self.update(&padding[..padding_length]);
+        self.update(bit_length.to_le_bytes());
+
+        let mut digest = [0; 16];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// One-shot form, only the tests need it so far
+#[cfg(test)]
+pub fn compute(data: impl AsRef<[u8]>) -> Digest {
+    let mut context = Context::new();
+    context.update(data);
+    context.finalize()
+}
+
+fn process_block(state: &mut [u32; 4], block: &[u8; BLOCK_SIZE]) {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+
+    for step in 0..64 {
+        let (mixed, index) = match step / 16 {
+            0 => ((b & c) | (!b & d), step),
+            1 => ((d & b) | (!d & c), (5 * step + 1) % 16),
+            2 => (b ^ c ^ d, (3 * step + 5) % 16),
+            _ => (c ^ (b | !d), (7 * step) % 16),
+        };
+
+        let rotated = a
+            .wrapping_add(mixed)
+            .wrapping_add(SINES[step])
+            .wrapping_add(words[index])
+            .rotate_left(SHIFTS[step]);
+
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(rotated);
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_hex(digest: Digest) -> String {
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn rfc_1321_test_vectors() {
+        let vectors = [
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            (
+                "abcdefghijklmnopqrstuvwxyz",
+                "c3fcd3d76192e4007dfb496cca67e13b",
+            ),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+
+        for (input, expected) in vectors {
+            assert_eq!(expected, to_hex(compute(input)), "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn streaming_updates_match_single_update() {
+        let text =
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+
+        for split in 0..=text.len() {
+            let mut context = Context::new();
+            context.update(&text[..split]);
+            context.update(&text[split..]);
+            assert_eq!(compute(text), context.finalize(), "split at {split}");
+        }
+    }
+
+    #[test]
+    fn padding_boundaries() {
+        // Lengths around 56 and 64 bytes exercise the extra padding block
+        let expected = [
+            (55, "ef1772b6dff9a122358552954ad0df65"),
+            (56, "3b0c8ac703f828b04c6c197006d17218"),
+            (63, "b06521f39153d618550606be297466d5"),
+            (64, "014842d480b571495a4a0363793f7367"),
+        ];
+
+        for (length, digest) in expected {
+            assert_eq!(
+                digest,
+                to_hex(compute("a".repeat(length))),
+                "length {length}"
+            );
+        }
+    }
+
+    #[test]
+    fn cloned_context_continues_independently() {
+        let mut prefix = Context::new();
+        prefix.update("abcdef");
+
+        let mut first = prefix.clone();
+        first.update("609043");
+        assert_eq!("000001dbbfa3a5c83a2d506429c7b00e", to_hex(first.finalize()));
+
+        let mut second = prefix;
+        second.update("1");
+        assert_eq!(to_hex(compute("abcdef1")), to_hex(second.finalize()));
+    }
+}
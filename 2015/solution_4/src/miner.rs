@@ -0,0 +1,186 @@
+use std::ops::Range;
+
+use crate::md5::{Context, Digest};
+use crate::nonce::NonceBuffer;
+
+#[derive(Debug, PartialEq)]
+pub struct Match {
+    pub nonce: u32,
+    pub digest: Digest,
+}
+
+// Walks a nonce range in increasing order and yields every nonce whose
+// digest of `secret + nonce` satisfies the predicate.
+pub struct Miner<P> {
+    secret_state: Context,
+    buffer: NonceBuffer,
+    end: u32,
+    predicate: P,
+}
+
+impl<P: Fn(&Digest) -> bool> Miner<P> {
+    pub fn new(secret: &str, nonces: Range<u32>, predicate: P) -> Self {
+        // The secret is absorbed once; each nonce only hashes its own digits on a copy of the state.
+        let mut secret_state = Context::new();
+        secret_state.update(secret);
+
+        Self {
+            secret_state,
+            buffer: NonceBuffer::new(nonces.start),
+            end: nonces.end,
+            predicate,
+        }
+    }
+}
+
+impl<P: Fn(&Digest) -> bool> Iterator for Miner<P> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.value() < self.end {
+            let mut state = self.secret_state.clone();
+            state.update(self.buffer.as_bytes());
+            // Returns e.g. dc 94 5c 58 4c be 97 b6 97 7e 88 3d 9d a5 ae 18
+            let digest = state.finalize();
+            let nonce = self.buffer.value();
+            self.buffer.increment();
+
+            if (self.predicate)(&digest) {
+                return Some(Match { nonce, digest });
+            }
+        }
+
+        None
+    }
+}
+
+pub mod predicates {
+    use crate::md5::Digest;
+
+    // Checks that the hex representation of the hash starts with `zeros` zero nibbles.
+    // Odd counts are handled by checking the high nibble of the last byte.
+    pub fn has_leading_zeros(hash: &[u8], zeros: usize) -> bool {
+        let full_bytes = zeros / 2;
+        if hash.len() < full_bytes + zeros % 2 {
+            return false;
+        }
+
+        if hash[..full_bytes].iter().any(|byte| *byte != 0) {
+            return false;
+        }
+
+        zeros.is_multiple_of(2) || hash[full_bytes] < 0x10
+    }
+
+    pub fn leading_zeros(zeros: usize) -> impl Fn(&Digest) -> bool {
+        move |digest| has_leading_zeros(digest, zeros)
+    }
+
+    // Matches the start of the hex digest against a pattern where `?` stands for any digit,
+    // e.g. "00?f". A plain hex string is simply a prefix.
+    pub fn hex_pattern(pattern: &str) -> Result<impl Fn(&Digest) -> bool, &'static str> {
+        let pattern = pattern
+            .chars()
+            .map(|char| match char {
+                '?' => Ok(None),
+                _ => match char.to_digit(16) {
+                    Some(nibble) => Ok(Some(nibble as u8)),
+                    None => Err("Pattern may only contain hex digits and '?'"),
+                },
+            })
+            .collect::<Result<Vec<Option<u8>>, _>>()?;
+
+        if pattern.len() > 32 {
+            return Err("Pattern is longer than an MD5 digest");
+        }
+
+        Ok(move |digest: &Digest| {
+            pattern
+                .iter()
+                .zip(nibbles(digest))
+                .all(|(expected, nibble)| expected.is_none_or(|expected| expected == nibble))
+        })
+    }
+
+    // Any hex digit appearing three times in a row anywhere in the digest
+    pub fn contains_triple(digest: &Digest) -> bool {
+        let nibbles: Vec<u8> = nibbles(digest).collect();
+        nibbles
+            .windows(3)
+            .any(|window| window[0] == window[1] && window[1] == window[2])
+    }
+
+    fn nibbles(digest: &Digest) -> impl Iterator<Item = u8> + '_ {
+        digest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::predicates::*;
+    use super::*;
+
+    #[test]
+    fn leading_zeros_even_counts() {
+        assert!(has_leading_zeros(&[0x00, 0x00, 0x0f], 4));
+        assert!(!has_leading_zeros(&[0x00, 0x01, 0x0f], 4));
+        assert!(has_leading_zeros(&[0xff], 0));
+    }
+
+    #[test]
+    fn leading_zeros_odd_counts() {
+        assert!(has_leading_zeros(&[0x00, 0x00, 0x0f], 5));
+        assert!(!has_leading_zeros(&[0x00, 0x00, 0x10], 5));
+        assert!(has_leading_zeros(&[0x01], 1));
+    }
+
+    #[test]
+    fn leading_zeros_longer_than_hash() {
+        assert!(!has_leading_zeros(&[0x00], 3));
+    }
+
+    #[test]
+    fn hex_pattern_with_wildcards() {
+        let mut digest = [0xff; 16];
+        digest[0] = 0x0a;
+        digest[1] = 0xbc;
+
+        assert!(hex_pattern("0abc").unwrap()(&digest));
+        assert!(hex_pattern("0A?c").unwrap()(&digest));
+        assert!(hex_pattern("").unwrap()(&digest));
+        assert!(!hex_pattern("00").unwrap()(&digest));
+        assert!(hex_pattern("0g").is_err());
+        assert!(hex_pattern(&"0".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn triple_anywhere_in_digest() {
+        let mut digest = [0x12; 16];
+        assert!(!contains_triple(&digest));
+
+        digest[7] = 0x33;
+        digest[8] = 0x3a;
+        assert!(contains_triple(&digest));
+    }
+
+    #[test]
+    fn matches_come_in_increasing_nonce_order() {
+        let nonces: Vec<u32> = Miner::new("abcdef", 0..2000, leading_zeros(2))
+            .map(|found| found.nonce)
+            .collect();
+
+        assert!(!nonces.is_empty());
+        assert!(nonces.windows(2).all(|pair| pair[0] < pair[1]));
+        for nonce in nonces {
+            let digest = crate::md5::compute(format!("abcdef{nonce}"));
+            assert!(has_leading_zeros(&digest, 2));
+        }
+    }
+
+    #[test]
+    fn range_start_is_respected() {
+        let mut miner = Miner::new("abcdef", 609043..609044, leading_zeros(5));
+        assert_eq!(609043, miner.next().unwrap().nonce);
+        assert_eq!(None, miner.next());
+    }
+}
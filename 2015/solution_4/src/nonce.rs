@@ -0,0 +1,78 @@
+// u32::MAX has 10 decimal digits
+const MAX_DIGITS: usize = 10;
+
+// Decimal representation of a nonce kept as ASCII digits, incremented in place
+// so the hot loop never has to format or allocate.
+pub struct NonceBuffer {
+    digits: [u8; MAX_DIGITS],
+    start: usize,
+    value: u32,
+}
+
+impl NonceBuffer {
+    pub fn new(value: u32) -> Self {
+        let mut digits = [b'0'; MAX_DIGITS];
+        let mut start = MAX_DIGITS - 1;
+        let mut rest = value;
+
+        for index in (0..MAX_DIGITS).rev() {
+            digits[index] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+            if digits[index] != b'0' {
+                start = index;
+            }
+        }
+
+        Self {
+            digits,
+            start,
+            value,
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.digits[self.start..]
+    }
+
+    pub fn increment(&mut self) {
+        self.value += 1;
+
+        for index in (0..MAX_DIGITS).rev() {
+            if self.digits[index] == b'9' {
+                self.digits[index] = b'0';
+                continue;
+            }
+
+            self.digits[index] += 1;
+            self.start = self.start.min(index);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn new_buffer_matches_to_string() {
+        for value in [0, 1, 9, 10, 609043, u32::MAX] {
+            let buffer = NonceBuffer::new(value);
+            assert_eq!(value.to_string().as_bytes(), buffer.as_bytes());
+        }
+    }
+
+    #[test]
+    fn increment_carries_over_digits() {
+        let mut buffer = NonceBuffer::new(0);
+        for value in 1..=1200u32 {
+            buffer.increment();
+            assert_eq!(value, buffer.value());
+            assert_eq!(value.to_string().as_bytes(), buffer.as_bytes());
+        }
+    }
+}
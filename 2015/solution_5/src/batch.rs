@@ -0,0 +1,173 @@
+use std::io::BufRead;
+use std::thread;
+
+use crate::line_checker::LineChecker;
+
+//Lines read from a stream before they are handed to the threads
+const CHUNK_LINES: usize = 64 * 1024;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchCounts {
+    pub lines: usize,
+    pub good: usize,
+    //Lines each top level rule passed, in the order the rules were added
+    pub per_rule: Vec<usize>,
+}
+
+impl BatchCounts {
+    fn add_line(&mut self, line_checker: &mut LineChecker, line: &str) {
+        let good = line_checker.check_all_rules(line);
+        if self.per_rule.is_empty() {
+            self.per_rule = vec![0; line_checker.passed_rules().count()];
+        }
+
+        self.lines += 1;
+        self.good += good as usize;
+        for (count, passed) in self.per_rule.iter_mut().zip(line_checker.passed_rules()) {
+            *count += passed as usize;
+        }
+    }
+
+    fn merge(&mut self, other: BatchCounts) {
+        if self.per_rule.is_empty() {
+            self.per_rule = vec![0; other.per_rule.len()];
+        }
+
+        self.lines += other.lines;
+        self.good += other.good;
+        for (count, other_count) in self.per_rule.iter_mut().zip(other.per_rule) {
+            *count += other_count;
+        }
+    }
+}
+
+/*
+Checks the lines on `threads` threads. LineChecker keeps per-line state, so every thread
+builds its own with `factory` instead of sharing one.
+*/
+pub fn check_lines<S, F>(factory: F, lines: &[S], threads: usize) -> BatchCounts
+where
+    S: AsRef<str> + Sync,
+    F: Fn() -> LineChecker + Sync,
+{
+    let chunk_size = lines.len().div_ceil(threads.max(1)).max(1);
+    let factory = &factory;
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = lines
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut line_checker = factory();
+                    let mut counts = BatchCounts::default();
+                    for line in chunk {
+                        counts.add_line(&mut line_checker, line.as_ref());
+                    }
+                    counts
+                })
+            })
+            .collect();
+
+        let mut counts = BatchCounts::default();
+        for worker in workers {
+            counts.merge(worker.join().expect("Batch worker panicked"));
+        }
+        counts
+    })
+}
+
+//Like check_lines, but reads the lines in chunks so the whole input is never in memory
+pub fn check_reader<R, F>(factory: F, reader: R, threads: usize) -> std::io::Result<BatchCounts>
+where
+    R: BufRead,
+    F: Fn() -> LineChecker + Sync,
+{
+    let mut counts = BatchCounts::default();
+    let mut lines = reader.lines();
+
+    loop {
+        let chunk = lines
+            .by_ref()
+            .take(CHUNK_LINES)
+            .collect::<std::io::Result<Vec<String>>>()?;
+        if chunk.is_empty() {
+            return Ok(counts);
+        }
+
+        counts.merge(check_lines(&factory, &chunk, threads));
+    }
+}
+
+#[cfg(test)]
+fn part_one() -> LineChecker {
+    crate::config::parse("vowels >= 3\nrecurring_letters >= 2\nforbidden").unwrap()
+}
+
+#[test]
+fn test_counts_lines_per_rule() {
+    let lines = [
+        "ugknbfddgicrmopn",
+        "aaa",
+        "jchzalrnumimnmhp",
+        "haegwjzuvuyypxyu",
+        "dvszwmarrgswjxmb",
+    ];
+
+    for threads in [1, 2, 8] {
+        assert_eq!(
+            BatchCounts {
+                lines: 5,
+                good: 2,
+                per_rule: vec![4, 4, 4],
+            },
+            check_lines(part_one, &lines, threads)
+        );
+    }
+}
+
+#[test]
+fn test_reader_matches_sequential_check() {
+    use crate::testing::Rng;
+
+    let mut rng = Rng::new(7);
+    let alphabet = ['a', 'e', 'x', 'y', 'z'];
+    let lines: Vec<String> = (0..1000).map(|_| rng.string(&alphabet, 10)).collect();
+
+    let mut line_checker = part_one();
+    let good = lines.iter().filter(|line| line_checker.check(line)).count();
+
+    //The trailing newline keeps a final empty line
+    let text = lines.join("\n") + "\n";
+    let counts = check_reader(part_one, text.as_bytes(), 4).unwrap();
+    assert_eq!(1000, counts.lines);
+    assert_eq!(good, counts.good);
+}
+
+#[test]
+fn test_empty_input() {
+    let lines: [&str; 0] = [];
+    assert_eq!(BatchCounts::default(), check_lines(part_one, &lines, 4));
+}
+
+// Run with `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_parallel_batch() {
+    use crate::testing::Rng;
+    use std::time::Instant;
+
+    let mut rng = Rng::new(1);
+    let alphabet: Vec<char> = ('a'..='z').collect();
+    let lines: Vec<String> = (0..2_000_000).map(|_| rng.string(&alphabet, 16)).collect();
+
+    for threads in [1, 2, 4, 8] {
+        let start = Instant::now();
+        let counts = check_lines(part_one, &lines, threads);
+        println!(
+            "{threads} threads: {} good of {} lines in {:?}",
+            counts.good,
+            counts.lines,
+            start.elapsed()
+        );
+    }
+}
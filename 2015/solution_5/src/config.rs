@@ -0,0 +1,380 @@
+use std::fmt::Display;
+
+use crate::helpers::Rule;
+use crate::line_checker::LineChecker;
+use crate::rules::combinators::{AllOf, AnyOf, AtLeast, Not};
+use crate::rules::forbidden_chars::ForbiddenCharsRule;
+use crate::rules::has_pairs::{HasPairsRule, PairCount};
+use crate::rules::regex_rule::RegexRule;
+use crate::rules::repeat_with_gap::RepeatWithGapRule;
+use crate::rules::run_length::RunLengthRule;
+use crate::rules::vowels_rule::{CharClass, VowelRule, DEFAULT_VOWELS};
+
+/*
+Rule set description, one rule per line. Top level rules must all pass.
+
+    # part one
+    vowels >= 3
+    recurring_letters >= 2
+    forbidden ab,cd,pq,xy
+
+Rules may take options after their parameter:
+
+    pairs >= 2 length 3 total
+    vowels >= 3 chars aeiouyäöü ignore_case
+    recurring_letters 2 exactly
+    repeat_with_gap 1..=2
+
+A regex rule takes the rest of the line verbatim as its pattern:
+
+    regex ^[a-m]+(ab|cd)$
+
+Blocks combine the rules inside them:
+
+    any {
+        pairs >= 1
+        not {
+            repeat_with_gap 1
+        }
+    }
+    at_least 2 { ... }
+*/
+
+#[derive(Debug, PartialEq)]
+pub struct ConfigError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl ConfigError {
+    fn new(line: usize, reason: &str) -> Self {
+        Self {
+            line,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.reason)
+    }
+}
+
+enum Block {
+    All(AllOf),
+    Any(AnyOf),
+    Not(Option<Box<dyn Rule>>),
+    AtLeast(AtLeast),
+}
+
+impl Block {
+    fn push(&mut self, rule: Box<dyn Rule>, line: usize) -> Result<(), ConfigError> {
+        match self {
+            Block::All(all) => all.add_rule(rule),
+            Block::Any(any) => any.add_rule(rule),
+            Block::AtLeast(at_least) => at_least.add_rule(rule),
+            Block::Not(inner) => {
+                if inner.is_some() {
+                    return Err(ConfigError::new(line, "A not block takes a single rule"));
+                }
+                *inner = Some(rule);
+            }
+        }
+        Ok(())
+    }
+
+    fn close(self, line: usize) -> Result<Box<dyn Rule>, ConfigError> {
+        Ok(match self {
+            Block::All(all) => Box::new(all),
+            Block::Any(any) => Box::new(any),
+            Block::AtLeast(at_least) => Box::new(at_least),
+            Block::Not(Some(rule)) => Box::new(Not::new(rule)),
+            Block::Not(None) => return Err(ConfigError::new(line, "Empty not block")),
+        })
+    }
+}
+
+pub fn parse(text: &str) -> Result<LineChecker, ConfigError> {
+    let mut line_checker = LineChecker::new();
+    //Innermost open block last, with the line it was opened on
+    let mut open_blocks: Vec<(Block, usize)> = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let rule = if line == "}" {
+            let Some((block, _)) = open_blocks.pop() else {
+                return Err(ConfigError::new(number, "Unmatched }"));
+            };
+            block.close(number)?
+        } else if let Some(header) = line.strip_suffix('{') {
+            open_blocks.push((parse_block(header.trim(), number)?, number));
+            continue;
+        } else {
+            parse_rule(line, number)?
+        };
+
+        match open_blocks.last_mut() {
+            Some((block, _)) => block.push(rule, number)?,
+            None => line_checker.add_rule(rule),
+        }
+    }
+
+    if let Some((_, opened_at)) = open_blocks.last() {
+        return Err(ConfigError::new(*opened_at, "Block is never closed"));
+    }
+
+    Ok(line_checker)
+}
+
+fn parse_block(header: &str, line: usize) -> Result<Block, ConfigError> {
+    let (name, argument) = split_rule(header);
+    match (name, argument) {
+        ("all", None) => Ok(Block::All(AllOf::new())),
+        ("any", None) => Ok(Block::Any(AnyOf::new())),
+        ("not", None) => Ok(Block::Not(None)),
+        ("at_least", Some(threshold)) => {
+            Ok(Block::AtLeast(AtLeast::new(parse_number(threshold, line)?)))
+        }
+        _ => Err(ConfigError::new(line, "Unknown block")),
+    }
+}
+
+fn parse_rule(text: &str, line: usize) -> Result<Box<dyn Rule>, ConfigError> {
+    //Taken before split_rule, which would eat a leading >= of the pattern
+    if let Some(pattern) = text.strip_prefix("regex ") {
+        return match RegexRule::new(pattern.trim()) {
+            Ok(rule) => Ok(Box::new(rule)),
+            Err(e) => Err(ConfigError::new(line, &format!("Invalid regex. {e}"))),
+        };
+    }
+
+    let (name, argument) = split_rule(text);
+    let Some(argument) = argument else {
+        return match name {
+            "forbidden" => Ok(Box::new(ForbiddenCharsRule::new())),
+            _ => Err(ConfigError::new(line, "Missing rule parameter")),
+        };
+    };
+
+    Ok(match name {
+        "vowels" => Box::new(parse_vowels(argument, line)?),
+        "recurring_letters" => Box::new(parse_recurring_letters(argument, line)?),
+        "pairs" => Box::new(parse_pairs(argument, line)?),
+        "repeat_with_gap" => Box::new(parse_repeat_with_gap(argument, line)?),
+        "forbidden" => {
+            let substrings: Vec<&str> = argument.split(',').map(str::trim).collect();
+            if substrings.iter().any(|substring| substring.is_empty()) {
+                return Err(ConfigError::new(
+                    line,
+                    "Forbidden substrings cannot be empty",
+                ));
+            }
+            Box::new(ForbiddenCharsRule::with_substrings(&substrings))
+        }
+        _ => return Err(ConfigError::new(line, "Unknown rule")),
+    })
+}
+
+//"vowels >= 3 chars aeiouyäö ignore_case": threshold, then an optional char class
+fn parse_vowels(argument: &str, line: usize) -> Result<VowelRule, ConfigError> {
+    let mut words = argument.split_whitespace();
+    let threshold = parse_number(words.next().unwrap_or_default(), line)?;
+    let mut vowels = None;
+    let mut ignore_case = false;
+
+    while let Some(word) = words.next() {
+        match word {
+            "chars" => match words.next() {
+                Some(chars) => vowels = Some(CharClass::new(chars)),
+                None => return Err(ConfigError::new(line, "Missing vowel chars")),
+            },
+            "ignore_case" => ignore_case = true,
+            _ => return Err(ConfigError::new(line, "Unknown vowels option")),
+        }
+    }
+
+    if vowels.is_none() && !ignore_case {
+        return Ok(VowelRule::new(threshold));
+    }
+
+    let mut vowels = vowels.unwrap_or_else(|| CharClass::new(DEFAULT_VOWELS));
+    if ignore_case {
+        vowels = vowels.ignoring_case();
+    }
+    Ok(VowelRule::with_class(threshold, vowels))
+}
+
+//"pairs >= 2 length 3 total": occurrences, then optional pair length and counting mode
+fn parse_pairs(argument: &str, line: usize) -> Result<HasPairsRule, ConfigError> {
+    let mut words = argument.split_whitespace();
+    let occurences = parse_number(words.next().unwrap_or_default(), line)?;
+    let mut rule = HasPairsRule::new(occurences);
+
+    while let Some(word) = words.next() {
+        rule = match word {
+            "distinct" => rule.with_counting(PairCount::Distinct),
+            "total" => rule.with_counting(PairCount::Total),
+            "length" => {
+                let length: usize = parse_number(words.next().unwrap_or_default(), line)?;
+                if length == 0 {
+                    return Err(ConfigError::new(line, "Pair length must be at least 1"));
+                }
+                rule.with_pair_length(length)
+            }
+            _ => return Err(ConfigError::new(line, "Unknown pairs option")),
+        };
+    }
+
+    Ok(rule)
+}
+
+//"recurring_letters >= 2" or "recurring_letters 2 exactly"
+fn parse_recurring_letters(argument: &str, line: usize) -> Result<RunLengthRule, ConfigError> {
+    let mut words = argument.split_whitespace();
+    let length = parse_number(words.next().unwrap_or_default(), line)?;
+
+    match (words.next(), words.next()) {
+        (None, _) => Ok(RunLengthRule::at_least(length)),
+        (Some("exactly"), None) => Ok(RunLengthRule::exactly(length)),
+        _ => Err(ConfigError::new(line, "Unknown recurring_letters option")),
+    }
+}
+
+//"repeat_with_gap 1" or a range of gaps like "repeat_with_gap 1..=2"
+fn parse_repeat_with_gap(argument: &str, line: usize) -> Result<RepeatWithGapRule, ConfigError> {
+    let Some((start, end)) = argument.split_once("..=") else {
+        return Ok(RepeatWithGapRule::new(parse_number(argument, line)?));
+    };
+
+    let start: usize = parse_number(start.trim(), line)?;
+    let end: usize = parse_number(end.trim(), line)?;
+    if start > end {
+        return Err(ConfigError::new(line, "Gap range is empty"));
+    }
+    Ok(RepeatWithGapRule::with_gaps(start..=end))
+}
+
+//Splits "name >= 3" or "name 3" into the name and its parameter
+fn split_rule(text: &str) -> (&str, Option<&str>) {
+    let Some((name, argument)) = text.split_once(char::is_whitespace) else {
+        return (text, None);
+    };
+
+    let argument = argument.trim();
+    let argument = argument.strip_prefix(">=").unwrap_or(argument).trim();
+    (name, Some(argument))
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str, line: usize) -> Result<T, ConfigError> {
+    text.parse()
+        .map_err(|_| ConfigError::new(line, "Rule parameter is not a valid number"))
+}
+
+#[test]
+fn test_parses_part_one() {
+    let config = "
+        # part one
+        vowels >= 3
+        recurring_letters >= 2
+        forbidden ab,cd,pq,xy
+    ";
+    let mut line_checker = parse(config).unwrap();
+
+    assert!(line_checker.check("ugknbfddgicrmopn"));
+    assert!(!line_checker.check("jchzalrnumimnmhp"));
+    assert!(!line_checker.check("haegwjzuvuyypxyu"));
+    assert!(!line_checker.check("dvszwmarrgswjxmb"));
+}
+
+#[test]
+fn test_parses_custom_forbidden_substrings() {
+    let mut line_checker = parse("forbidden ba, xyz").unwrap();
+
+    assert!(line_checker.check("abxy"));
+    assert!(!line_checker.check("cba"));
+    assert!(!line_checker.check("axyz"));
+}
+
+#[test]
+fn test_parses_nested_blocks() {
+    let config = "
+        any {
+            all {
+                pairs 1
+                repeat_with_gap 1
+            }
+            not {
+                vowels 1
+            }
+        }
+        at_least 1 {
+            forbidden
+        }
+    ";
+    let mut line_checker = parse(config).unwrap();
+
+    assert!(line_checker.check("qjhvhtzxzqqjkmpb"));
+    assert!(!line_checker.check("xyz"));
+    assert!(line_checker.check("xz"));
+    assert!(!line_checker.check("uurcxstgmygtbstg"));
+}
+
+#[test]
+fn test_parses_pairs_options() {
+    let mut line_checker = parse("pairs >= 2 length 3 total").unwrap();
+
+    assert!(line_checker.check("abcabcabc"));
+    assert!(!line_checker.check("abcxabc"));
+}
+
+#[test]
+fn test_parses_vowel_class() {
+    let mut line_checker = parse("vowels >= 2 chars aeiouäö ignore_case").unwrap();
+
+    assert!(line_checker.check("xÄÖ"));
+    assert!(line_checker.check("xAe"));
+    assert!(!line_checker.check("xyÜ"));
+}
+
+#[test]
+fn test_parses_runs_and_gap_ranges() {
+    let mut line_checker = parse("recurring_letters 2 exactly\nrepeat_with_gap 1..=2").unwrap();
+
+    assert!(line_checker.check("abbcb"));
+    assert!(!line_checker.check("abbbcb"));
+    assert!(!line_checker.check("abbcde"));
+}
+
+#[test]
+fn test_parses_regex_rule() {
+    let mut line_checker = parse("regex ^[a-m]+(ab|cd)$\nvowels >= 1").unwrap();
+
+    assert!(line_checker.check("helicd"));
+    assert!(!line_checker.check("helicdx"));
+    assert!(!line_checker.check("zeab"));
+}
+
+#[test]
+fn test_reports_errors_with_line_numbers() {
+    let error = |config: &str| parse(config).err().unwrap();
+
+    assert_eq!(3, error("vowels 3\n\nvowels three").line);
+    assert_eq!(1, error("sparkles 3").line);
+    assert_eq!(2, error("vowels 3\n}").line);
+    assert_eq!(1, error("any {\nvowels 3").line);
+    assert_eq!(3, error("not {\nvowels 3\nvowels 2\n}").line);
+    assert_eq!(1, error("forbidden ab,,cd").line);
+    assert_eq!(1, error("pairs").line);
+    assert_eq!(1, error("pairs 1 length 0").line);
+    assert_eq!(1, error("pairs 1 sideways").line);
+    assert_eq!(1, error("vowels 1 chars").line);
+    assert_eq!(2, error("vowels 1\nregex (unclosed").line);
+    assert_eq!(1, error("recurring_letters 2 mostly").line);
+    assert_eq!(1, error("repeat_with_gap 2..=1").line);
+}
@@ -1,7 +1,114 @@
+use crate::verdict::{Evidence, Verdict};
+
 pub type Byte = u8;
 
+// View over the bytes (or chars) of the line seen so far. The last one is being processed.
+pub struct Window<'a, T = Byte> {
+    seen: &'a [T],
+}
+
+impl<'a, T: Copy> Window<'a, T> {
+    pub fn new(seen: &'a [T]) -> Self {
+        assert!(!seen.is_empty(), "A window needs at least the current byte");
+        Self { seen }
+    }
+
+    pub fn index(&self) -> usize {
+        self.seen.len() - 1
+    }
+
+    pub fn current(&self) -> T {
+        self.seen[self.index()]
+    }
+
+    //Byte `offset` positions before the current one. back(0) is the current byte.
+    pub fn back(&self, offset: usize) -> Option<T> {
+        let index = self.index().checked_sub(offset)?;
+        Some(self.seen[index])
+    }
+
+    //The last `length` bytes, ending with the current one
+    pub fn tail(&self, length: usize) -> Option<&'a [T]> {
+        let start = self.seen.len().checked_sub(length)?;
+        Some(&self.seen[start..])
+    }
+
+    pub fn seen(&self) -> &'a [T] {
+        self.seen
+    }
+}
+
 pub trait Rule {
-    fn process_char(&mut self, line: &String, index: usize, char: Byte);
+    //Called for every byte of the line
+    fn process(&mut self, _window: &Window) {}
+    //Called for every char of the line, as long as processes_chars() is true for some rule
+    fn process_char(&mut self, _window: &Window<char>) {}
+    fn processes_chars(&self) -> bool {
+        false
+    }
+    //Called once with the whole line after its bytes and chars, even when it is empty
+    fn finish(&mut self, _line: &str) {}
     fn passes(&self) -> bool;
+    //True once further bytes can no longer change what passes() returns
+    fn is_settled(&self) -> bool {
+        false
+    }
     fn reset(&mut self);
+    //Verdict with whatever the rule found in the line so far
+    fn explain(&self) -> Verdict {
+        Verdict::new("rule", self.passes(), Evidence::Nothing)
+    }
+}
+
+//Lets rule trees built at runtime (e.g. from a config file) be added like any other rule
+impl<R: Rule + ?Sized> Rule for Box<R> {
+    fn process(&mut self, window: &Window) {
+        (**self).process(window)
+    }
+
+    fn process_char(&mut self, window: &Window<char>) {
+        (**self).process_char(window)
+    }
+
+    fn processes_chars(&self) -> bool {
+        (**self).processes_chars()
+    }
+
+    fn finish(&mut self, line: &str) {
+        (**self).finish(line)
+    }
+
+    fn passes(&self) -> bool {
+        (**self).passes()
+    }
+
+    fn is_settled(&self) -> bool {
+        (**self).is_settled()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn explain(&self) -> Verdict {
+        (**self).explain()
+    }
+}
+
+#[test]
+fn test_window_looks_back() {
+    let window = Window::new(b"abc");
+    assert_eq!(2, window.index());
+    assert_eq!(b'c', window.current());
+    assert_eq!(Some(b'c'), window.back(0));
+    assert_eq!(Some(b'a'), window.back(2));
+    assert_eq!(None, window.back(3));
+    assert_eq!(Some(&b"bc"[..]), window.tail(2));
+    assert_eq!(Some(&b"abc"[..]), window.tail(3));
+    assert_eq!(None, window.tail(4));
+
+    let chars: Vec<char> = "añb".chars().collect();
+    let window = Window::new(&chars[..2]);
+    assert_eq!('ñ', window.current());
+    assert_eq!(Some('a'), window.back(1));
 }
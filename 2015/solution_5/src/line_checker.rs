@@ -1,12 +1,12 @@
-use crate::helpers::Byte;
 use crate::helpers::Rule;
+use crate::helpers::Window;
+use crate::verdict::Verdict;
 struct RuleRow {
     rule: Box<dyn Rule>,
     passed: bool,
 }
 
 pub struct LineChecker {
-    line: String,
     rules: Vec<RuleRow>,
 }
 
@@ -20,47 +20,110 @@ impl LineChecker {
     }
 
     pub fn new() -> Self {
-        Self {
-            line: String::from(""),
-            rules: vec![],
-        }
+        Self { rules: vec![] }
+    }
+
+    pub fn check(&mut self, text: &str) -> bool {
+        self.scan(text, true);
+        self.is_good_string()
+    }
+
+    //Like check, but reads the whole line and reports what every rule found
+    pub fn check_detailed(&mut self, text: &str) -> (bool, Vec<Verdict>) {
+        self.scan(text, false);
+        let verdicts: Vec<Verdict> = self.rules.iter().map(|row| row.rule.explain()).collect();
+        //Decided by the verdicts themselves so that the two can never disagree
+        let passed = verdicts.iter().all(|verdict| verdict.passed);
+        (passed, verdicts)
+    }
+
+    //Like check, but reads the whole line so that passed_rules is accurate for every rule
+    pub fn check_all_rules(&mut self, text: &str) -> bool {
+        self.scan(text, false);
+        self.is_good_string()
+    }
+
+    //Whether each top level rule passed the last line, in the order they were added
+    pub fn passed_rules(&self) -> impl Iterator<Item = bool> + '_ {
+        self.rules.iter().map(|row| row.passed)
+    }
+
+    //Top level rule descriptions, in the order they were added
+    pub fn rule_names(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .map(|row| row.rule.explain().rule)
+            .collect()
     }
 
-    pub fn check(&mut self, text: &String) -> bool {
-        self.line = text.to_string();
+    fn scan(&mut self, text: &str, stop_early: bool) {
         self.reset_rules();
-        for (index, char) in text.as_bytes().iter().enumerate() {
-            self.run_rules_on_byte(index, *char);
-        }
+        let bytes = text.as_bytes();
 
-        let is_good_string = self.is_good_string();
+        //Lines are only decoded when a rule works on chars
+        let processes_chars = self.rules.iter().any(|row| row.rule.processes_chars());
+        let chars: Vec<char> = if processes_chars {
+            text.chars().collect()
+        } else {
+            vec![]
+        };
 
-        is_good_string
+        for (char_index, (start, char)) in text.char_indices().enumerate() {
+            for index in start..start + char.len_utf8() {
+                self.run_rules_on_window(&Window::new(&bytes[..=index]));
+            }
+            if processes_chars {
+                self.run_rules_on_char_window(&Window::new(&chars[..=char_index]));
+            }
+
+            if stop_early && self.is_decided() {
+                break;
+            }
+        }
+
+        //Rules can pass without seeing a single char, e.g. on an empty line
+        for rule_row in &mut self.rules {
+            rule_row.rule.finish(text);
+            rule_row.passed = rule_row.rule.passes();
+        }
     }
 
-    fn run_rules_on_byte(&mut self, index: usize, char: Byte) {
+    fn run_rules_on_window(&mut self, window: &Window) {
         for rule_row in &mut self.rules {
             let rule = &mut rule_row.rule;
-            rule.process_char(&self.line, index, char);
-            if rule.passes() {
-                rule_row.passed = true;
-            } else {
-                rule_row.passed = false;
+            if !rule.is_settled() {
+                rule.process(window);
             }
+            rule_row.passed = rule.passes();
         }
     }
 
-    fn is_good_string(&mut self) -> bool {
-        let mut is_good = true;
+    fn run_rules_on_char_window(&mut self, window: &Window<char>) {
+        for rule_row in &mut self.rules {
+            let rule = &mut rule_row.rule;
+            if !rule.is_settled() {
+                rule.process_char(window);
+            }
+            rule_row.passed = rule.passes();
+        }
+    }
 
+    //The line is decided once every rule is settled or a settled rule already fails
+    fn is_decided(&self) -> bool {
+        let mut all_settled = true;
         for rule_row in self.rules.iter() {
-            if rule_row.passed == false {
-                is_good = false;
-                break;
+            if !rule_row.rule.is_settled() {
+                all_settled = false;
+            } else if !rule_row.passed {
+                return true;
             }
         }
 
-        is_good
+        all_settled
+    }
+
+    fn is_good_string(&mut self) -> bool {
+        self.rules.iter().all(|rule_row| rule_row.passed)
     }
 
     fn reset_rules(&mut self) {
@@ -70,3 +133,147 @@ impl LineChecker {
         }
     }
 }
+
+#[cfg(test)]
+struct CountingRule {
+    processed: std::rc::Rc<std::cell::Cell<usize>>,
+    settle_after: usize,
+    passes: bool,
+}
+
+#[cfg(test)]
+impl Rule for CountingRule {
+    fn process(&mut self, _: &Window) {
+        self.processed.set(self.processed.get() + 1);
+    }
+
+    fn passes(&self) -> bool {
+        self.passes
+    }
+
+    fn is_settled(&self) -> bool {
+        self.processed.get() >= self.settle_after
+    }
+
+    fn reset(&mut self) {
+        self.processed.set(0);
+    }
+}
+
+#[test]
+fn test_stops_once_all_rules_settle() {
+    let processed = std::rc::Rc::new(std::cell::Cell::new(0));
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(CountingRule {
+        processed: processed.clone(),
+        settle_after: 3,
+        passes: true,
+    });
+
+    assert!(line_checker.check("abcdefgh"));
+    assert_eq!(3, processed.get());
+}
+
+#[test]
+fn test_stops_once_a_settled_rule_fails() {
+    let failing = std::rc::Rc::new(std::cell::Cell::new(0));
+    let pending = std::rc::Rc::new(std::cell::Cell::new(0));
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(CountingRule {
+        processed: failing.clone(),
+        settle_after: 2,
+        passes: false,
+    });
+    line_checker.add_rule(CountingRule {
+        processed: pending.clone(),
+        settle_after: usize::MAX,
+        passes: true,
+    });
+
+    assert!(!line_checker.check("abcdefgh"));
+    assert_eq!(2, failing.get());
+    assert_eq!(2, pending.get());
+}
+
+#[test]
+fn test_settled_forbidden_pair_skips_rest_of_line() {
+    use crate::rules::forbidden_chars::ForbiddenCharsRule;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(ForbiddenCharsRule::new());
+    line_checker.add_rule(VowelRule::new(3));
+
+    assert!(!line_checker.check("abaaa"));
+    assert!(line_checker.check("eaaa"));
+}
+
+#[test]
+fn test_check_detailed_explains_every_rule() {
+    use crate::rules::forbidden_chars::ForbiddenCharsRule;
+    use crate::rules::has_pairs::HasPairsRule;
+    use crate::rules::repeat_with_gap::RepeatWithGapRule;
+    use crate::rules::run_length::RunLengthRule;
+    use crate::rules::vowels_rule::VowelRule;
+    use crate::verdict::Evidence;
+
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(ForbiddenCharsRule::new());
+    line_checker.add_rule(VowelRule::new(3));
+    line_checker.add_rule(RunLengthRule::at_least(2));
+    line_checker.add_rule(HasPairsRule::new(1));
+    line_checker.add_rule(RepeatWithGapRule::new(1));
+
+    let (passed, verdicts) = line_checker.check_detailed("haegwjzuvuyypxyu");
+    assert!(!passed);
+    assert_eq!(
+        vec![
+            Evidence::ForbiddenSubstring {
+                substring: String::from("xy"),
+                offset: 13,
+            },
+            //Vowels are still collected after the forbidden pair decided the line
+            Evidence::Vowels {
+                offsets: vec![1, 2, 7],
+            },
+            Evidence::Run {
+                letter: String::from("y"),
+                offset: 10,
+                length: 2,
+            },
+            Evidence::RepeatedPairs { repeats: vec![] },
+            Evidence::GapRepeat {
+                first: 7,
+                second: 9,
+            },
+        ],
+        verdicts
+            .into_iter()
+            .map(|verdict| verdict.evidence)
+            .collect::<Vec<Evidence>>()
+    );
+}
+
+#[test]
+fn test_check_detailed_empty_line() {
+    use crate::rules::forbidden_chars::ForbiddenCharsRule;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(ForbiddenCharsRule::new());
+
+    let (passed, verdicts) = line_checker.check_detailed("");
+    assert!(passed);
+    assert!(verdicts[0].passed);
+
+    line_checker.add_rule(VowelRule::new(1));
+    let (passed, verdicts) = line_checker.check_detailed("");
+    assert!(!passed);
+    assert_eq!(
+        vec![true, false],
+        verdicts
+            .iter()
+            .map(|verdict| verdict.passed)
+            .collect::<Vec<bool>>()
+    );
+}
@@ -1,34 +1,95 @@
+use std::env;
 use std::fs;
+use std::io::BufReader;
+use std::thread;
 
+mod batch;
+mod config;
 mod helpers;
 mod line_checker;
 mod rules;
+#[cfg(test)]
+mod testing;
+mod verdict;
 
-use rules::has_pairs::HasPairsRule;
-use rules::repeat_with_gap::RepeatWithGapRule;
+fn explain_naughty_strings(line_checker: &mut line_checker::LineChecker, text: &str) {
+    for line in text.lines() {
+        //Good lines are skipped with the early stopping check
+        if line_checker.check(line) {
+            continue;
+        }
+
+        let (_, verdicts) = line_checker.check_detailed(line);
+
+        println!("{line} is naughty:");
+        for verdict in verdicts {
+            for explanation in verdict.to_string().lines() {
+                println!("  {explanation}");
+            }
+        }
+    }
+}
 
+// Usage: solution_5 [--explain] [RULES_FILE...]
+// Each rule file is checked against input.txt on every core. Defaults to both puzzle parts.
+// Prints the good strings and how many lines passed each top level rule.
+// With --explain, every naughty line is printed with what each rule found in it.
 fn main() {
-    let mut line_checker = line_checker::LineChecker::new();
-    line_checker.add_rule(HasPairsRule::new(1));
-    line_checker.add_rule(RepeatWithGapRule::new(1));
+    let mut config_paths: Vec<String> = env::args().skip(1).collect();
+    let explain = config_paths.iter().any(|arg| arg == "--explain");
+    config_paths.retain(|arg| arg != "--explain");
+    if config_paths.is_empty() {
+        config_paths = vec![
+            String::from("part_one.rules"),
+            String::from("part_two.rules"),
+        ];
+    }
 
-    let text = match fs::read_to_string("input.txt") {
-        Ok(text) => text,
-        Err(e) => panic!("Error reading file: {e}"),
-    };
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
 
-    let mut num_good_strings_modified = 0;
-    for line in text.lines() {
-        if line_checker.check(&String::from(line)) {
-            num_good_strings_modified += 1;
+    for config_path in config_paths {
+        let config = match fs::read_to_string(&config_path) {
+            Ok(config) => config,
+            Err(e) => panic!("Error reading file at {config_path}. Error {e}"),
+        };
+
+        let mut line_checker = match config::parse(&config) {
+            Ok(line_checker) => line_checker,
+            Err(e) => {
+                eprintln!("Invalid rules in {config_path}. {e}");
+                continue;
+            }
+        };
+
+        if explain {
+            match fs::read_to_string("input.txt") {
+                Ok(text) => explain_naughty_strings(&mut line_checker, &text),
+                Err(e) => panic!("Error reading file: {e}"),
+            }
         }
-    }
 
-    println!("Num of good strings via struct: {num_good_strings_modified}");
+        //Every thread parses its own copy of the rules
+        let factory = || config::parse(&config).expect("Rules were already parsed");
+        let counts = match fs::File::open("input.txt")
+            .and_then(|input| batch::check_reader(factory, BufReader::new(input), threads))
+        {
+            Ok(counts) => counts,
+            Err(e) => panic!("Error reading file: {e}"),
+        };
+
+        println!("{config_path}: {} good strings", counts.good);
+        for (rule, passed) in line_checker.rule_names().iter().zip(&counts.per_rule) {
+            println!("  {rule}: {passed} of {} lines", counts.lines);
+        }
+    }
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_is_good_string() {
+    use rules::has_pairs::HasPairsRule;
+    use rules::repeat_with_gap::RepeatWithGapRule;
+
     let mut line_checker = line_checker::LineChecker::new();
     line_checker.add_rule(RepeatWithGapRule::new(1));
     line_checker.add_rule(HasPairsRule::new(1));
@@ -0,0 +1,394 @@
+use crate::helpers::{Rule, Window};
+use crate::verdict::{Evidence, Verdict};
+
+/*
+Rules composed out of other rules, so policies can be written as a tree, e.g.
+AllOf[VowelRule(3), RunLengthRule(2), ForbiddenCharsRule]
+*/
+struct Children {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Children {
+    fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    fn push<T: Rule + 'static>(&mut self, rule: T) {
+        self.rules.push(Box::new(rule));
+    }
+
+    fn process(&mut self, window: &Window) {
+        for rule in self.rules.iter_mut() {
+            if !rule.is_settled() {
+                rule.process(window);
+            }
+        }
+    }
+
+    fn process_char(&mut self, window: &Window<char>) {
+        for rule in self.rules.iter_mut() {
+            if !rule.is_settled() {
+                rule.process_char(window);
+            }
+        }
+    }
+
+    fn processes_chars(&self) -> bool {
+        self.rules.iter().any(|rule| rule.processes_chars())
+    }
+
+    fn finish(&mut self, line: &str) {
+        for rule in self.rules.iter_mut() {
+            rule.finish(line);
+        }
+    }
+
+    fn reset(&mut self) {
+        for rule in self.rules.iter_mut() {
+            rule.reset();
+        }
+    }
+
+    fn explain(&self) -> Evidence {
+        Evidence::Children(self.rules.iter().map(|rule| rule.explain()).collect())
+    }
+
+    fn num_passing(&self) -> usize {
+        self.rules.iter().filter(|rule| rule.passes()).count()
+    }
+
+    fn num_settled_with(&self, passes: bool) -> usize {
+        self.rules
+            .iter()
+            .filter(|rule| rule.is_settled() && rule.passes() == passes)
+            .count()
+    }
+}
+
+pub struct AllOf {
+    children: Children,
+}
+
+impl AllOf {
+    pub fn new() -> Self {
+        Self {
+            children: Children::new(),
+        }
+    }
+
+    pub fn add_rule<T: Rule + 'static>(&mut self, rule: T) {
+        self.children.push(rule);
+    }
+
+    #[cfg(test)]
+    pub fn with<T: Rule + 'static>(mut self, rule: T) -> Self {
+        self.add_rule(rule);
+        self
+    }
+}
+
+impl Rule for AllOf {
+    fn process(&mut self, window: &Window) {
+        self.children.process(window);
+    }
+
+    fn process_char(&mut self, window: &Window<char>) {
+        self.children.process_char(window);
+    }
+
+    fn processes_chars(&self) -> bool {
+        self.children.processes_chars()
+    }
+
+    fn finish(&mut self, line: &str) {
+        self.children.finish(line);
+    }
+
+    fn passes(&self) -> bool {
+        self.children.num_passing() == self.children.rules.len()
+    }
+
+    fn is_settled(&self) -> bool {
+        let children = &self.children;
+        children.num_settled_with(false) > 0
+            || children.num_settled_with(true) == children.rules.len()
+    }
+
+    fn reset(&mut self) {
+        self.children.reset();
+    }
+
+    fn explain(&self) -> Verdict {
+        Verdict::new("all of", self.passes(), self.children.explain())
+    }
+}
+
+pub struct AnyOf {
+    children: Children,
+}
+
+impl AnyOf {
+    pub fn new() -> Self {
+        Self {
+            children: Children::new(),
+        }
+    }
+
+    pub fn add_rule<T: Rule + 'static>(&mut self, rule: T) {
+        self.children.push(rule);
+    }
+
+    #[cfg(test)]
+    pub fn with<T: Rule + 'static>(mut self, rule: T) -> Self {
+        self.add_rule(rule);
+        self
+    }
+}
+
+impl Rule for AnyOf {
+    fn process(&mut self, window: &Window) {
+        self.children.process(window);
+    }
+
+    fn process_char(&mut self, window: &Window<char>) {
+        self.children.process_char(window);
+    }
+
+    fn processes_chars(&self) -> bool {
+        self.children.processes_chars()
+    }
+
+    fn finish(&mut self, line: &str) {
+        self.children.finish(line);
+    }
+
+    fn passes(&self) -> bool {
+        self.children.num_passing() > 0
+    }
+
+    fn is_settled(&self) -> bool {
+        let children = &self.children;
+        children.num_settled_with(true) > 0
+            || children.num_settled_with(false) == children.rules.len()
+    }
+
+    fn reset(&mut self) {
+        self.children.reset();
+    }
+
+    fn explain(&self) -> Verdict {
+        Verdict::new("any of", self.passes(), self.children.explain())
+    }
+}
+
+pub struct Not {
+    rule: Box<dyn Rule>,
+}
+
+impl Not {
+    pub fn new<T: Rule + 'static>(rule: T) -> Self {
+        Self {
+            rule: Box::new(rule),
+        }
+    }
+}
+
+impl Rule for Not {
+    fn process(&mut self, window: &Window) {
+        self.rule.process(window);
+    }
+
+    fn process_char(&mut self, window: &Window<char>) {
+        self.rule.process_char(window);
+    }
+
+    fn processes_chars(&self) -> bool {
+        self.rule.processes_chars()
+    }
+
+    fn finish(&mut self, line: &str) {
+        self.rule.finish(line);
+    }
+
+    fn passes(&self) -> bool {
+        !self.rule.passes()
+    }
+
+    fn is_settled(&self) -> bool {
+        self.rule.is_settled()
+    }
+
+    fn reset(&mut self) {
+        self.rule.reset();
+    }
+
+    fn explain(&self) -> Verdict {
+        Verdict::new(
+            "not",
+            self.passes(),
+            Evidence::Children(vec![self.rule.explain()]),
+        )
+    }
+}
+
+//Passes when at least `threshold` of the child rules pass
+pub struct AtLeast {
+    threshold: usize,
+    children: Children,
+}
+
+impl AtLeast {
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            children: Children::new(),
+        }
+    }
+
+    pub fn add_rule<T: Rule + 'static>(&mut self, rule: T) {
+        self.children.push(rule);
+    }
+
+    #[cfg(test)]
+    pub fn with<T: Rule + 'static>(mut self, rule: T) -> Self {
+        self.add_rule(rule);
+        self
+    }
+}
+
+impl Rule for AtLeast {
+    fn process(&mut self, window: &Window) {
+        self.children.process(window);
+    }
+
+    fn process_char(&mut self, window: &Window<char>) {
+        self.children.process_char(window);
+    }
+
+    fn processes_chars(&self) -> bool {
+        self.children.processes_chars()
+    }
+
+    fn finish(&mut self, line: &str) {
+        self.children.finish(line);
+    }
+
+    fn passes(&self) -> bool {
+        self.children.num_passing() >= self.threshold
+    }
+
+    fn is_settled(&self) -> bool {
+        let children = &self.children;
+        let can_still_pass = children.rules.len() - children.num_settled_with(false);
+        children.num_settled_with(true) >= self.threshold || can_still_pass < self.threshold
+    }
+
+    fn reset(&mut self) {
+        self.children.reset();
+    }
+
+    fn explain(&self) -> Verdict {
+        Verdict::new(
+            &format!("at least {}", self.threshold),
+            self.passes(),
+            self.children.explain(),
+        )
+    }
+}
+
+#[test]
+fn test_all_of_requires_every_rule() {
+    use crate::line_checker;
+    use crate::rules::forbidden_chars::ForbiddenCharsRule;
+    use crate::rules::run_length::RunLengthRule;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(
+        AllOf::new()
+            .with(VowelRule::new(3))
+            .with(RunLengthRule::at_least(2))
+            .with(ForbiddenCharsRule::new()),
+    );
+
+    assert!(line_checker.check("ugknbfddgicrmopn"));
+    assert!(line_checker.check("aaa"));
+    assert!(!line_checker.check("jchzalrnumimnmhp"));
+    assert!(!line_checker.check("haegwjzuvuyypxyu"));
+    assert!(!line_checker.check("dvszwmarrgswjxmb"));
+}
+
+#[test]
+fn test_any_of_requires_one_rule() {
+    use crate::line_checker;
+    use crate::rules::run_length::RunLengthRule;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(
+        AnyOf::new()
+            .with(VowelRule::new(3))
+            .with(RunLengthRule::at_least(2)),
+    );
+
+    assert!(line_checker.check("aei"));
+    assert!(line_checker.check("xx"));
+    assert!(!line_checker.check("xyz"));
+}
+
+#[test]
+fn test_not_inverts_rule() {
+    use crate::line_checker;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(Not::new(VowelRule::new(2)));
+
+    assert!(line_checker.check("xyza"));
+    assert!(!line_checker.check("aexyz"));
+}
+
+#[test]
+fn test_at_least_counts_passing_rules() {
+    use crate::line_checker;
+    use crate::rules::forbidden_chars::ForbiddenCharsRule;
+    use crate::rules::run_length::RunLengthRule;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(
+        AtLeast::new(2)
+            .with(VowelRule::new(3))
+            .with(RunLengthRule::at_least(2))
+            .with(ForbiddenCharsRule::new()),
+    );
+
+    assert!(line_checker.check("aeixx"));
+    assert!(line_checker.check("aei"));
+    assert!(!line_checker.check("abxx"));
+    assert!(!line_checker.check("ab"));
+}
+
+#[test]
+fn test_combinators_nest() {
+    use crate::line_checker;
+    use crate::rules::has_pairs::HasPairsRule;
+    use crate::rules::repeat_with_gap::RepeatWithGapRule;
+    use crate::rules::vowels_rule::VowelRule;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(
+        AnyOf::new()
+            .with(
+                AllOf::new()
+                    .with(HasPairsRule::new(1))
+                    .with(RepeatWithGapRule::new(1)),
+            )
+            .with(Not::new(VowelRule::new(1))),
+    );
+
+    assert!(line_checker.check("qjhvhtzxzqqjkmpb"));
+    assert!(line_checker.check("xyz"));
+    assert!(!line_checker.check("uurcxstgmygtbstg"));
+}
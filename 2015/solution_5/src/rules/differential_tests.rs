@@ -0,0 +1,240 @@
+/*
+Property tests: every rule is checked against a naive reference predicate, or a RegexRule
+where the criterion needs no backreferences. Lines are random, with the feature a rule looks
+for planted in half of them so that both outcomes come up often.
+*/
+use crate::helpers::Rule;
+use crate::line_checker::LineChecker;
+use crate::rules::combinators::{AllOf, AnyOf, AtLeast, Not};
+use crate::rules::forbidden_chars::ForbiddenCharsRule;
+use crate::rules::has_pairs::{HasPairsRule, PairCount};
+use crate::rules::regex_rule::RegexRule;
+use crate::rules::repeat_with_gap::RepeatWithGapRule;
+use crate::rules::run_length::RunLengthRule;
+use crate::rules::vowels_rule::{CharClass, VowelRule};
+use crate::testing::{assert_agrees, Rng};
+
+//Few letters so that repeats, pairs and forbidden pairs come up often.
+//U+AAAA is encoded as ea aa aa, so byte-based rules would see runs inside it
+const ALPHABET: [char; 11] = ['a', 'b', 'c', 'd', 'e', 'p', 'q', 'x', 'y', 'ñ', '\u{aaaa}'];
+
+fn random_line(rng: &mut Rng) -> String {
+    rng.string(&ALPHABET, 12)
+}
+
+//Plain random lines, and random lines with one of the features planted in them
+fn planted_line(features: &'static [&'static str]) -> impl FnMut(&mut Rng) -> String {
+    move |rng| {
+        if rng.below(2) == 0 {
+            return random_line(rng);
+        }
+        let feature = features[rng.below(features.len())];
+        rng.planted(&ALPHABET, 8, feature)
+    }
+}
+
+fn regex_reference(rule: impl Rule + 'static) -> impl FnMut(&str) -> bool {
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(rule);
+    move |line| line_checker.check(line)
+}
+
+fn count_vowels(line: &str, vowels: &str) -> usize {
+    line.chars().filter(|char| vowels.contains(*char)).count()
+}
+
+//Lengths of the maximal runs of identical chars, e.g. [1, 3, 1] for "abbbc"
+fn run_lengths(line: &str) -> Vec<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    chars
+        .chunk_by(|first, second| first == second)
+        .map(<[char]>::len)
+        .collect()
+}
+
+fn has_gap_repeat(line: &str, gap: usize) -> bool {
+    let chars: Vec<char> = line.chars().collect();
+    chars.windows(gap + 2).any(|span| span[0] == span[gap + 1])
+}
+
+//Repeats of every distinct pair, counting non-overlapping occurrences from the left
+fn pair_repeats(line: &str, pair_length: usize) -> Vec<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut pairs: Vec<&[char]> = chars.windows(pair_length).collect();
+    pairs.sort();
+    pairs.dedup();
+
+    pairs
+        .into_iter()
+        .map(|pair| {
+            let mut occurrences = 0;
+            let mut start = 0;
+            while start + pair_length <= chars.len() {
+                if &chars[start..start + pair_length] == pair {
+                    occurrences += 1;
+                    start += pair_length;
+                } else {
+                    start += 1;
+                }
+            }
+            occurrences - 1
+        })
+        .collect()
+}
+
+#[test]
+fn test_vowel_rule_matches_regex() {
+    let pattern = "[aeiou].*[aeiou].*[aeiou]";
+    assert_agrees(
+        VowelRule::new(3),
+        regex_reference(RegexRule::new(pattern).unwrap()),
+        planted_line(&["ae", "aea", "eee"]),
+    );
+}
+
+#[test]
+fn test_vowel_rule_matches_naive() {
+    for threshold in 0..=4 {
+        assert_agrees(
+            VowelRule::new(threshold),
+            |line| count_vowels(line, "aeiou") >= threshold as usize,
+            planted_line(&["a", "ae", "aeiou"]),
+        );
+    }
+
+    assert_agrees(
+        VowelRule::with_class(2, CharClass::new("añ")),
+        |line| count_vowels(line, "añ") >= 2,
+        planted_line(&["ñ", "ññ", "añ"]),
+    );
+    assert_agrees(
+        VowelRule::with_class(2, CharClass::new("AÑ").ignoring_case()),
+        |line| count_vowels(line, "añ") >= 2,
+        planted_line(&["ñ", "ññ", "añ"]),
+    );
+}
+
+#[test]
+fn test_forbidden_chars_rule_matches_regex() {
+    let pattern = "ab|cd|pq|xy";
+    assert_agrees(
+        ForbiddenCharsRule::new(),
+        regex_reference(Not::new(RegexRule::new(pattern).unwrap())),
+        planted_line(&["ab", "cd", "pq", "xy"]),
+    );
+}
+
+#[test]
+fn test_forbidden_chars_rule_matches_naive() {
+    let substrings = ["ñx", "abc", "\u{aaaa}b", "b"];
+    assert_agrees(
+        ForbiddenCharsRule::with_substrings(&substrings),
+        |line| !substrings.iter().any(|substring| line.contains(substring)),
+        planted_line(&["ñx", "abc", "\u{aaaa}b"]),
+    );
+}
+
+#[test]
+fn test_run_length_rule_matches_naive() {
+    for length in 1..=3 {
+        assert_agrees(
+            RunLengthRule::at_least(length),
+            |line| run_lengths(line).iter().any(|run| *run >= length),
+            planted_line(&["aa", "ñññ", "\u{aaaa}\u{aaaa}"]),
+        );
+        assert_agrees(
+            RunLengthRule::exactly(length),
+            |line| run_lengths(line).contains(&length),
+            planted_line(&["aa", "ñññ", "\u{aaaa}\u{aaaa}"]),
+        );
+    }
+}
+
+#[test]
+fn test_repeat_with_gap_rule_matches_naive() {
+    for (start, end) in [(0, 0), (1, 1), (2, 2), (1, 2), (0, 3)] {
+        assert_agrees(
+            RepeatWithGapRule::with_gaps(start..=end),
+            |line| (start..=end).any(|gap| has_gap_repeat(line, gap)),
+            planted_line(&["xyx", "xyzx", "aña", "ñ\u{aaaa}\u{aaaa}ñ"]),
+        );
+    }
+}
+
+#[test]
+fn test_has_pairs_rule_matches_naive() {
+    for pair_length in 1..=3 {
+        for occurences in 1..=3 {
+            let features = &["xyxy", "abab", "ñañ", "aaaa", "xyzxyz"];
+            assert_agrees(
+                HasPairsRule::new(occurences).with_pair_length(pair_length),
+                |line| {
+                    let repeating = pair_repeats(line, pair_length)
+                        .into_iter()
+                        .filter(|repeats| *repeats > 0)
+                        .count();
+                    repeating >= occurences
+                },
+                planted_line(features),
+            );
+            assert_agrees(
+                HasPairsRule::new(occurences)
+                    .with_pair_length(pair_length)
+                    .with_counting(PairCount::Total),
+                |line| pair_repeats(line, pair_length).into_iter().sum::<usize>() >= occurences,
+                planted_line(features),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_regex_rule_matches_naive() {
+    assert_agrees(
+        RegexRule::new("^ab|ñ$").unwrap(),
+        |line| line.starts_with("ab") || line.ends_with('ñ'),
+        planted_line(&["ab", "ñ"]),
+    );
+    assert_agrees(
+        RegexRule::new("^(ab)*$").unwrap(),
+        |line| line.len() % 2 == 0 && line.as_bytes().chunks(2).all(|pair| pair == b"ab"),
+        planted_line(&["", "ab", "abab"]),
+    );
+}
+
+#[test]
+fn test_combinators_match_naive() {
+    let vowels = |line: &str| count_vowels(line, "aeiou") >= 2;
+    let double = |line: &str| run_lengths(line).iter().any(|run| *run >= 2);
+    let gap = |line: &str| has_gap_repeat(line, 1);
+    let features = &["aa", "xyx", "ae"];
+
+    assert_agrees(
+        AllOf::new()
+            .with(VowelRule::new(2))
+            .with(RunLengthRule::at_least(2)),
+        |line| vowels(line) && double(line),
+        planted_line(features),
+    );
+    assert_agrees(
+        AnyOf::new()
+            .with(VowelRule::new(2))
+            .with(Not::new(RepeatWithGapRule::new(1))),
+        |line| vowels(line) || !gap(line),
+        planted_line(features),
+    );
+    assert_agrees(
+        AtLeast::new(2)
+            .with(VowelRule::new(2))
+            .with(RunLengthRule::at_least(2))
+            .with(RepeatWithGapRule::new(1)),
+        |line| {
+            [vowels(line), double(line), gap(line)]
+                .iter()
+                .filter(|passed| **passed)
+                .count()
+                >= 2
+        },
+        planted_line(features),
+    );
+}
@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+
+use crate::helpers::Byte;
+
+pub type State = usize;
+
+const ROOT: State = 0;
+
+/*
+Aho-Corasick automaton compiled down to a full transition table, so matching
+any number of substrings costs a single lookup per byte.
+*/
+pub struct Automaton {
+    transitions: Vec<[State; 256]>,
+    //Length of the shortest pattern ending in each state, if any
+    matches: Vec<Option<usize>>,
+}
+
+impl Automaton {
+    pub fn new(patterns: &[&str]) -> Self {
+        let mut automaton = Self {
+            transitions: vec![[ROOT; 256]],
+            matches: vec![None],
+        };
+
+        //Build the trie. Missing edges point back at the root for now.
+        let mut has_edge = vec![[false; 256]];
+        for pattern in patterns {
+            let mut state = ROOT;
+            for &byte in pattern.as_bytes() {
+                if !has_edge[state][byte as usize] {
+                    automaton.transitions.push([ROOT; 256]);
+                    automaton.matches.push(None);
+                    has_edge.push([false; 256]);
+                    has_edge[state][byte as usize] = true;
+                    automaton.transitions[state][byte as usize] = automaton.transitions.len() - 1;
+                }
+                state = automaton.transitions[state][byte as usize];
+            }
+
+            let length = pattern.len();
+            automaton.matches[state] =
+                Some(automaton.matches[state].map_or(length, |m| m.min(length)));
+        }
+
+        //Breadth first, fill in missing edges from the failure state and inherit its matches
+        let mut failure = vec![ROOT; automaton.transitions.len()];
+        let mut queue: VecDeque<State> = VecDeque::new();
+        for (byte, edge) in has_edge[ROOT].iter().enumerate() {
+            if *edge {
+                queue.push_back(automaton.transitions[ROOT][byte]);
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let fallback = failure[state];
+            automaton.matches[state] = match (automaton.matches[state], automaton.matches[fallback])
+            {
+                (Some(own), Some(inherited)) => Some(own.min(inherited)),
+                (own, inherited) => own.or(inherited),
+            };
+
+            for (byte, edge) in has_edge[state].iter().enumerate() {
+                if *edge {
+                    let next = automaton.transitions[state][byte];
+                    failure[next] = automaton.transitions[fallback][byte];
+                    queue.push_back(next);
+                } else {
+                    automaton.transitions[state][byte] = automaton.transitions[fallback][byte];
+                }
+            }
+        }
+
+        automaton
+    }
+
+    pub fn start(&self) -> State {
+        ROOT
+    }
+
+    pub fn next(&self, state: State, byte: Byte) -> State {
+        self.transitions[state][byte as usize]
+    }
+
+    //Length of a pattern ending at the byte that led into `state`
+    pub fn matched(&self, state: State) -> Option<usize> {
+        self.matches[state]
+    }
+}
+
+#[cfg(test)]
+fn first_match_end(automaton: &Automaton, text: &str) -> Option<usize> {
+    let mut state = automaton.start();
+    for (index, byte) in text.bytes().enumerate() {
+        state = automaton.next(state, byte);
+        if automaton.matched(state).is_some() {
+            return Some(index);
+        }
+    }
+    None
+}
+
+#[test]
+fn test_matches_patterns_of_any_length() {
+    let automaton = Automaton::new(&["he", "she", "hers", "x"]);
+
+    assert_eq!(Some(1), first_match_end(&automaton, "hers"));
+    assert_eq!(Some(3), first_match_end(&automaton, "ushe"));
+    assert_eq!(Some(3), first_match_end(&automaton, "abcx"));
+    assert_eq!(None, first_match_end(&automaton, "hhhh"));
+}
+
+#[test]
+fn test_follows_failure_links() {
+    //"bd" must be found after the partial "abcz" match falls back
+    let automaton = Automaton::new(&["abcz", "bd"]);
+
+    assert_eq!(Some(3), first_match_end(&automaton, "abbd"));
+    assert_eq!(Some(4), first_match_end(&automaton, "aabcz"));
+    assert_eq!(None, first_match_end(&automaton, "abcabc"));
+}
+
+#[test]
+fn test_reports_inherited_match_length() {
+    let automaton = Automaton::new(&["abcd", "bc"]);
+    let mut state = automaton.start();
+    for byte in "abc".bytes() {
+        state = automaton.next(state, byte);
+    }
+
+    assert_eq!(Some(2), automaton.matched(state));
+}
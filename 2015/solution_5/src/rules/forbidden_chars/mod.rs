@@ -1,56 +1,88 @@
-use crate::helpers::{Byte, Rule};
+use crate::helpers::{Rule, Window};
+use crate::verdict::{Evidence, Verdict};
+
+mod aho_corasick;
+use aho_corasick::{Automaton, State};
+
+const DEFAULT_SUBSTRINGS: [&str; 4] = ["ab", "cd", "pq", "xy"];
+
 pub struct ForbiddenCharsRule {
+    automaton: Automaton,
+    state: State,
     is_forbidden: bool,
+    //Char offset and text of the forbidden substring once found
+    found: Option<(usize, String)>,
 }
 
 impl ForbiddenCharsRule {
+    //Forbids the puzzle's ab, cd, pq and xy
     pub fn new() -> Self {
+        Self::with_substrings(&DEFAULT_SUBSTRINGS)
+    }
+
+    pub fn with_substrings(substrings: &[&str]) -> Self {
+        let automaton = Automaton::new(substrings);
         ForbiddenCharsRule {
+            state: automaton.start(),
+            automaton,
             is_forbidden: false,
+            found: None,
         }
     }
 }
 
 impl Rule for ForbiddenCharsRule {
-    /*
-    Checks if any of the following sequences are present in the string:
-    ab, cd, pq, xy
-    */
-    fn process_char(&mut self, line: &String, index: usize, char: Byte) {
-        //Skip first element as there's nothing to compare it against
-        if index == 0 {
-            return;
-        }
-
-        if self.is_forbidden == true {
+    //Checks if any of the forbidden substrings ends at the current byte
+    fn process(&mut self, window: &Window) {
+        if self.is_forbidden {
             return;
         }
 
-        let suspicious_chars = [b'b', b'd', b'q', b'y'];
-
-        if !suspicious_chars.contains(&char) {
-            self.is_forbidden = false;
-            return;
+        self.state = self.automaton.next(self.state, window.current());
+        if let Some(length) = self.automaton.matched(self.state) {
+            self.is_forbidden = true;
+            let substring = window.tail(length).unwrap_or_default();
+            //Reported in chars, like the offsets of every other rule
+            let start = window.index() + 1 - length;
+            let offset = String::from_utf8_lossy(&window.seen()[..start])
+                .chars()
+                .count();
+            self.found = Some((offset, String::from_utf8_lossy(substring).into_owned()));
         }
-
-        let text_as_bytes = &line[..index].as_bytes();
-        self.is_forbidden = if text_as_bytes[index - 1] == char - 1 {
-            true
-        } else {
-            false
-        };
     }
 
     fn passes(&self) -> bool {
         !self.is_forbidden
     }
 
+    fn is_settled(&self) -> bool {
+        self.is_forbidden
+    }
+
     fn reset(&mut self) {
+        self.state = self.automaton.start();
         self.is_forbidden = false;
+        self.found = None;
+    }
+
+    fn explain(&self) -> Verdict {
+        let name = "forbidden substrings";
+        match &self.found {
+            Some((offset, substring)) => Verdict::new(
+                name,
+                false,
+                Evidence::ForbiddenSubstring {
+                    substring: substring.clone(),
+                    offset: *offset,
+                },
+            ),
+            None => Verdict::new(name, true, Evidence::Nothing),
+        }
     }
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_has_forbidden_sequences() {
     use crate::line_checker;
 
@@ -64,3 +96,34 @@ fn test_has_forbidden_sequences() {
     let text = String::from("axyb");
     assert_eq!(false, line_checker.check(&text));
 }
+
+#[test]
+fn test_has_custom_forbidden_substrings() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(ForbiddenCharsRule::with_substrings(&["ba", "xyz", "q"]));
+
+    assert!(line_checker.check("abcdxy"));
+    assert!(!line_checker.check("cba"));
+    assert!(!line_checker.check("wxyz"));
+    assert!(!line_checker.check("q"));
+    assert!(line_checker.check("xyxy"));
+}
+
+#[test]
+fn test_forbidden_offset_counts_chars() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(ForbiddenCharsRule::new());
+
+    let (_, verdicts) = line_checker.check_detailed("ññ\u{aaaa}xy");
+    assert_eq!(
+        Evidence::ForbiddenSubstring {
+            substring: String::from("xy"),
+            offset: 3,
+        },
+        verdicts[0].evidence
+    );
+}
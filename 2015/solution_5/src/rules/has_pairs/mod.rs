@@ -1,49 +1,128 @@
-use crate::helpers::{Byte, Rule};
+use std::collections::HashMap;
+
+use crate::helpers::{Rule, Window};
+use crate::verdict::{Evidence, Verdict};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PairCount {
+    //Every pair that repeats counts once
+    Distinct,
+    //Every non-overlapping repeat counts, e.g. xyxyxy has two repeats of xy
+    Total,
+}
+
+struct Occurrences {
+    count: usize,
+    last_end: usize,
+}
+
 pub struct HasPairsRule {
     occurences: usize,
-    occurred: bool,
+    pair_length: usize,
+    counting: PairCount,
+    repeats: usize,
+    //Non-overlapping occurrences of every pair seen so far, counted greedily from the left
+    seen: HashMap<Vec<char>, Occurrences>,
+    //Every counted repeat with the start of the earlier and the repeated occurrence
+    found: Vec<(Vec<char>, usize, usize)>,
 }
 
 impl HasPairsRule {
     pub fn new(occurences: usize) -> Self {
         HasPairsRule {
-            occurences: occurences,
-            occurred: false,
+            occurences,
+            pair_length: 2,
+            counting: PairCount::Distinct,
+            repeats: 0,
+            seen: HashMap::new(),
+            found: vec![],
         }
     }
+
+    pub fn with_pair_length(mut self, pair_length: usize) -> Self {
+        assert!(pair_length > 0, "Pairs need at least one char");
+        self.pair_length = pair_length;
+        self
+    }
+
+    pub fn with_counting(mut self, counting: PairCount) -> Self {
+        self.counting = counting;
+        self
+    }
 }
 
 impl Rule for HasPairsRule {
-    fn process_char(&mut self, line: &String, index: usize, _: Byte) {
-        if self.occurred == true {
+    fn process_char(&mut self, window: &Window<char>) {
+        if self.passes() {
             return;
         }
 
-        //Length needs to be at least two pairs in size to make any sense
-        let min_text_length: usize = 4;
-        if index + 1 < min_text_length {
+        let Some(pair) = window.tail(self.pair_length) else {
             return;
-        }
+        };
 
-        let line_to_check = &line[..index - 1];
-        let pair_to_check = &line[index - 1..=index];
+        let index = window.index();
+        let Some(occurrences) = self.seen.get_mut(pair) else {
+            self.seen.insert(
+                pair.to_vec(),
+                Occurrences {
+                    count: 1,
+                    last_end: index,
+                },
+            );
+            return;
+        };
+
+        //The earlier occurrence must end before the current pair starts, so they don't overlap
+        if occurrences.last_end + self.pair_length > index {
+            return;
+        }
 
-        if line_to_check.contains(&pair_to_check) {
-            self.occurred = true;
+        let earlier_start = occurrences.last_end + 1 - self.pair_length;
+        occurrences.count += 1;
+        occurrences.last_end = index;
+        if self.counting == PairCount::Total || occurrences.count == 2 {
+            self.repeats += 1;
+            self.found
+                .push((pair.to_vec(), earlier_start, index + 1 - self.pair_length));
         }
     }
 
+    fn processes_chars(&self) -> bool {
+        true
+    }
+
     fn passes(&self) -> bool {
-        self.occurred == true
+        self.repeats >= self.occurences
+    }
+
+    fn is_settled(&self) -> bool {
+        self.passes()
     }
 
     fn reset(&mut self) {
-        self.occurences = 0;
-        self.occurred = false;
+        self.repeats = 0;
+        self.seen.clear();
+        self.found.clear();
+    }
+
+    fn explain(&self) -> Verdict {
+        let repeats = self
+            .found
+            .iter()
+            .map(|(pair, first, second)| (pair.iter().collect(), *first, *second))
+            .collect();
+
+        Verdict::new(
+            &format!("pairs >= {}", self.occurences),
+            self.passes(),
+            Evidence::RepeatedPairs { repeats },
+        )
     }
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_has_pairs_rule() {
     use crate::line_checker;
 
@@ -70,3 +149,70 @@ fn test_has_pairs_rule() {
     let text = String::from("aabcdefgaa");
     assert_eq!(true, line_checker.check(&text));
 }
+
+#[test]
+fn test_has_pairs_overlap_edge_cases() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(HasPairsRule::new(1));
+
+    // Test case: aa at 0 and 2 don't overlap even though aa at 1 does
+    assert!(line_checker.check("aaaa"));
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(HasPairsRule::new(2).with_counting(PairCount::Total));
+
+    // Test case: Only two non-overlapping aa fit in aaaa or aaaaa
+    assert!(!line_checker.check("aaaa"));
+    assert!(!line_checker.check("aaaaa"));
+    assert!(line_checker.check("aaaaaa"));
+}
+
+#[test]
+fn test_has_pairs_counts_occurrences() {
+    use crate::line_checker;
+
+    let mut distinct = line_checker::LineChecker::new();
+    distinct.add_rule(HasPairsRule::new(2));
+    let mut total = line_checker::LineChecker::new();
+    total.add_rule(HasPairsRule::new(3).with_counting(PairCount::Total));
+
+    // Test case: xy repeats twice, yx once
+    assert!(distinct.check("xyxyxy"));
+    assert!(total.check("xyxyxy"));
+
+    // Test case: xy repeats twice, nothing else does
+    assert!(!distinct.check("xybxycxy"));
+    assert!(!total.check("xybxycxy"));
+
+    // Test case: Two different pairs repeating once each
+    assert!(distinct.check("abcdabcd"));
+    assert!(total.check("abcdabcd"));
+}
+
+#[test]
+fn test_has_longer_pairs() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(HasPairsRule::new(1).with_pair_length(3));
+
+    assert!(line_checker.check("abcxabc"));
+    assert!(!line_checker.check("abcab"));
+    // Test case: Overlapping triples
+    assert!(!line_checker.check("aaaa"));
+    assert!(line_checker.check("aaaaaa"));
+}
+
+#[test]
+fn test_pairs_of_multi_byte_chars() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(HasPairsRule::new(1));
+
+    assert!(line_checker.check("ñxyñx"));
+    // Test case: ñ repeats, but no pair of chars does even though its bytes do
+    assert!(!line_checker.check("ñxñ"));
+}
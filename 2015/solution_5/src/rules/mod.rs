@@ -1,5 +1,10 @@
+pub mod combinators;
 pub mod forbidden_chars;
 pub mod has_pairs;
-pub mod recurrent_letters;
+pub mod regex_rule;
 pub mod repeat_with_gap;
+pub mod run_length;
 pub mod vowels_rule;
+
+#[cfg(test)]
+mod differential_tests;
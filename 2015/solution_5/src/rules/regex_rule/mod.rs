@@ -0,0 +1,87 @@
+use regex::Regex;
+
+use crate::helpers::Rule;
+use crate::verdict::{Evidence, Verdict};
+
+/*
+Passes when the pattern matches somewhere in the line. The regex crate has no
+backreferences, so criteria like "a letter that appears twice in a row" still
+need a hand-written rule.
+
+The pattern is searched once in the whole line when it ends, so that anchors like
+`$` keep their meaning and empty lines are matched too.
+*/
+pub struct RegexRule {
+    regex: Regex,
+    matched: bool,
+}
+
+impl RegexRule {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(pattern)?,
+            matched: false,
+        })
+    }
+}
+
+impl Rule for RegexRule {
+    fn finish(&mut self, line: &str) {
+        self.matched = self.regex.is_match(line);
+    }
+
+    fn passes(&self) -> bool {
+        self.matched
+    }
+
+    fn reset(&mut self) {
+        self.matched = false;
+    }
+
+    fn explain(&self) -> Verdict {
+        Verdict::new(
+            &format!("regex {}", self.regex.as_str()),
+            self.passes(),
+            Evidence::Nothing,
+        )
+    }
+}
+
+#[test]
+fn test_regex_rule_matches_anywhere() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RegexRule::new("ab|cd|pq|xy").unwrap());
+
+    assert!(line_checker.check("zzxyzz"));
+    assert!(!line_checker.check("zzzz"));
+    assert!(!line_checker.check(""));
+}
+
+#[test]
+fn test_regex_rule_end_anchor_applies_to_whole_line() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RegexRule::new("ab$").unwrap());
+
+    assert!(line_checker.check("xxab"));
+    assert!(!line_checker.check("xxabx"));
+}
+
+#[test]
+fn test_regex_rule_rejects_invalid_pattern() {
+    assert!(RegexRule::new("(unclosed").is_err());
+}
+
+#[test]
+fn test_regex_rule_matches_empty_line() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RegexRule::new("^$").unwrap());
+
+    assert!(line_checker.check(""));
+    assert!(!line_checker.check("a"));
+}
@@ -1,48 +1,86 @@
-use crate::helpers::{Byte, Rule};
+use std::ops::RangeInclusive;
+
+use crate::helpers::{Rule, Window};
+use crate::verdict::{Evidence, Verdict};
+
+//A char that repeats with `gap` chars in between, e.g. "x?x" for a gap of 1
 pub struct RepeatWithGapRule {
-    gap: usize,
-    occurred: bool,
+    gaps: RangeInclusive<usize>,
+    //Indexes of the char and its repeat once found
+    occurred_at: Option<(usize, usize)>,
 }
 
 impl RepeatWithGapRule {
     pub fn new(gap: usize) -> Self {
+        Self::with_gaps(gap..=gap)
+    }
+
+    //Any gap in the range counts, e.g. 1..=2 matches both "x?x" and "x??x"
+    pub fn with_gaps(gaps: RangeInclusive<usize>) -> Self {
         RepeatWithGapRule {
-            gap: gap,
-            occurred: false,
+            gaps,
+            occurred_at: None,
         }
     }
 }
 
 impl Rule for RepeatWithGapRule {
-    fn process_char(&mut self, line: &String, index: usize, char: Byte) {
-        if self.occurred == true {
+    fn process_char(&mut self, window: &Window<char>) {
+        if self.occurred_at.is_some() {
             return;
         }
 
-        //Checked string needs to be at least gap + 2 (1 adjusted for index) chars big to be processed
-        if index < self.gap + 1 {
-            return;
-        }
-
-        let line = &line[..index].as_bytes();
-
-        let start_char = line[index - (self.gap + 1)];
+        for gap in self.gaps.clone() {
+            //Checked string needs to be at least gap + 2 chars big to have a start char
+            let Some(start_char) = window.back(gap + 1) else {
+                return;
+            };
 
-        if start_char == char {
-            self.occurred = true;
+            if start_char == window.current() {
+                self.occurred_at = Some((window.index() - (gap + 1), window.index()));
+                return;
+            }
         }
     }
 
+    fn processes_chars(&self) -> bool {
+        true
+    }
+
     fn passes(&self) -> bool {
-        self.occurred == true
+        self.occurred_at.is_some()
+    }
+
+    fn is_settled(&self) -> bool {
+        self.passes()
     }
 
     fn reset(&mut self) {
-        self.occurred = false;
+        self.occurred_at = None;
+    }
+
+    fn explain(&self) -> Verdict {
+        let name = if self.gaps.start() == self.gaps.end() {
+            format!("repeat with gap {}", self.gaps.start())
+        } else {
+            format!(
+                "repeat with gap {}..={}",
+                self.gaps.start(),
+                self.gaps.end()
+            )
+        };
+
+        match self.occurred_at {
+            Some((first, second)) => {
+                Verdict::new(&name, true, Evidence::GapRepeat { first, second })
+            }
+            None => Verdict::new(&name, false, Evidence::Nothing),
+        }
     }
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_repeat_with_gap_rule() {
     use crate::line_checker;
 
@@ -67,3 +105,28 @@ fn test_repeat_with_gap_rule() {
     let text = String::from("xaaax");
     assert_eq!(true, line_checker.check(&text));
 }
+
+#[test]
+fn test_repeat_with_gap_range() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RepeatWithGapRule::with_gaps(1..=2));
+
+    assert!(line_checker.check("xyx"));
+    assert!(line_checker.check("xyzx"));
+    assert!(!line_checker.check("xx"));
+    assert!(!line_checker.check("xyzwx"));
+}
+
+#[test]
+fn test_repeat_with_multi_byte_gap() {
+    use crate::line_checker;
+
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RepeatWithGapRule::new(1));
+
+    // Test case: ñ is two bytes but a single char of gap
+    assert!(line_checker.check("aña"));
+    assert!(!line_checker.check("añña"));
+}
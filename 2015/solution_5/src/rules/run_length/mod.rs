@@ -0,0 +1,164 @@
+use crate::helpers::{Rule, Window};
+use crate::verdict::{Evidence, Verdict};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RunLength {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+//Runs of identical chars, e.g. the "xx" of part one. Works on chars so a multi-byte
+//letter is never split into a run of its bytes.
+pub struct RunLengthRule {
+    length: RunLength,
+    current: Option<char>,
+    run_start: usize,
+    run_length: usize,
+    //First run that fitted, as (letter, start, length)
+    found: Option<(char, usize, usize)>,
+}
+
+impl RunLengthRule {
+    pub fn at_least(length: usize) -> Self {
+        Self::new(RunLength::AtLeast(length))
+    }
+
+    pub fn exactly(length: usize) -> Self {
+        Self::new(RunLength::Exactly(length))
+    }
+
+    fn new(length: RunLength) -> Self {
+        Self {
+            length,
+            current: None,
+            run_start: 0,
+            run_length: 0,
+            found: None,
+        }
+    }
+
+    fn fits(&self, run_length: usize) -> bool {
+        match self.length {
+            RunLength::Exactly(length) => run_length == length,
+            RunLength::AtLeast(length) => run_length >= length,
+        }
+    }
+
+    //The run ending at the last processed char, if it already fits
+    fn current_run(&self) -> Option<(char, usize, usize)> {
+        let letter = self.current?;
+        self.fits(self.run_length)
+            .then_some((letter, self.run_start, self.run_length))
+    }
+}
+
+impl Rule for RunLengthRule {
+    fn process_char(&mut self, window: &Window<char>) {
+        if self.found.is_some() {
+            return;
+        }
+
+        let char = window.current();
+        if self.current == Some(char) {
+            self.run_length += 1;
+        } else {
+            //The previous run is over, so an exact length can no longer grow past it
+            if let Some(run) = self.current_run() {
+                self.found = Some(run);
+                return;
+            }
+            self.current = Some(char);
+            self.run_start = window.index();
+            self.run_length = 1;
+        }
+
+        if let RunLength::AtLeast(_) = self.length {
+            self.found = self.current_run();
+        }
+    }
+
+    fn processes_chars(&self) -> bool {
+        true
+    }
+
+    //A run of exactly the length at the end of the line passes too
+    fn passes(&self) -> bool {
+        self.found.is_some() || self.current_run().is_some()
+    }
+
+    fn is_settled(&self) -> bool {
+        self.found.is_some()
+    }
+
+    fn reset(&mut self) {
+        self.current = None;
+        self.run_start = 0;
+        self.run_length = 0;
+        self.found = None;
+    }
+
+    fn explain(&self) -> Verdict {
+        let name = match self.length {
+            RunLength::Exactly(length) => format!("recurring letters == {length}"),
+            RunLength::AtLeast(length) => format!("recurring letters >= {length}"),
+        };
+
+        match self.found.or_else(|| self.current_run()) {
+            Some((letter, offset, length)) => Verdict::new(
+                &name,
+                true,
+                Evidence::Run {
+                    letter: letter.to_string(),
+                    offset,
+                    length,
+                },
+            ),
+            None => Verdict::new(&name, false, Evidence::Nothing),
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn test_has_reoccuring_letters() {
+    use crate::line_checker;
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RunLengthRule::at_least(2));
+
+    let text = String::from("abcdefg");
+    assert_eq!(false, line_checker.check(&text));
+
+    let text = String::from("somethingxx");
+    assert_eq!(true, line_checker.check(&text));
+    let text = String::from("xxsomething");
+    assert_eq!(true, line_checker.check(&text));
+    let text = String::from("sometxxhing");
+    assert_eq!(true, line_checker.check(&text));
+    let text = String::from("xX");
+    assert_eq!(false, line_checker.check(&text));
+}
+
+#[test]
+fn test_runs_of_exact_length() {
+    use crate::line_checker;
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RunLengthRule::exactly(2));
+
+    assert!(line_checker.check("abbc"));
+    assert!(line_checker.check("abb"));
+    assert!(!line_checker.check("abbbc"));
+    // Test case: A longer run first doesn't hide a later exact one
+    assert!(line_checker.check("aaabcc"));
+    assert!(!line_checker.check("abc"));
+}
+
+#[test]
+fn test_runs_of_multi_byte_chars() {
+    use crate::line_checker;
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(RunLengthRule::at_least(2));
+
+    assert!(line_checker.check("aññb"));
+    // Test case: U+AAAA is encoded as ea aa aa, which is not a run of letters
+    assert!(!line_checker.check("a\u{aaaa}b"));
+}
@@ -1,38 +1,101 @@
-use crate::helpers::{Byte, Rule};
+use crate::helpers::{Rule, Window};
+use crate::verdict::{Evidence, Verdict};
+
+pub const DEFAULT_VOWELS: &str = "aeiou";
+
+//Set of characters a rule looks for, optionally ignoring case
+pub struct CharClass {
+    chars: Vec<char>,
+    ignore_case: bool,
+}
+
+impl CharClass {
+    pub fn new(chars: &str) -> Self {
+        Self {
+            chars: chars.chars().collect(),
+            ignore_case: false,
+        }
+    }
+
+    pub fn ignoring_case(mut self) -> Self {
+        self.chars = self
+            .chars
+            .iter()
+            .flat_map(|char| char.to_lowercase())
+            .collect();
+        self.ignore_case = true;
+        self
+    }
+
+    pub fn contains(&self, char: char) -> bool {
+        if self.ignore_case {
+            char.to_lowercase().any(|lower| self.chars.contains(&lower))
+        } else {
+            self.chars.contains(&char)
+        }
+    }
+}
 
 pub struct VowelRule {
     threshold: u32,
     num_vowels: u32,
+    vowels: CharClass,
+    offsets: Vec<usize>,
 }
 
 impl VowelRule {
     pub fn new(threshold: u32) -> Self {
+        Self::with_class(threshold, CharClass::new(DEFAULT_VOWELS))
+    }
+
+    pub fn with_class(threshold: u32, vowels: CharClass) -> Self {
         Self {
-            threshold: threshold,
+            threshold,
             num_vowels: 0,
+            vowels,
+            offsets: vec![],
         }
     }
 }
 
 impl Rule for VowelRule {
-    fn process_char(&mut self, _: &String, _: usize, char: Byte) {
-        let vowels = [b'a', b'e', b'i', b'o', b'u'];
-
-        if vowels.contains(&char) {
+    fn process_char(&mut self, window: &Window<char>) {
+        if self.vowels.contains(window.current()) {
             self.num_vowels += 1;
+            self.offsets.push(window.index());
         }
     }
 
+    fn processes_chars(&self) -> bool {
+        true
+    }
+
     fn passes(&self) -> bool {
         self.num_vowels >= self.threshold
     }
 
+    fn is_settled(&self) -> bool {
+        self.passes()
+    }
+
     fn reset(&mut self) {
         self.num_vowels = 0;
+        self.offsets.clear();
+    }
+
+    fn explain(&self) -> Verdict {
+        Verdict::new(
+            &format!("vowels >= {}", self.threshold),
+            self.passes(),
+            Evidence::Vowels {
+                offsets: self.offsets.clone(),
+            },
+        )
     }
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_has_num_of_vowels() {
     use crate::line_checker;
     let mut line_checker = line_checker::LineChecker::new();
@@ -54,3 +117,29 @@ fn test_has_num_of_vowels() {
     line_checker.add_rule(VowelRule::new(1));
     assert_eq!(true, line_checker.check(&text));
 }
+
+#[test]
+fn test_counts_custom_vowels() {
+    use crate::line_checker;
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(VowelRule::with_class(3, CharClass::new("aeiouyäöü")));
+
+    assert!(line_checker.check("mäöy"));
+    assert!(!line_checker.check("mÄÖy"));
+    // Test case: Multi-byte chars count once each
+    assert!(!line_checker.check("ñññä"));
+}
+
+#[test]
+fn test_counts_vowels_ignoring_case() {
+    use crate::line_checker;
+    let mut line_checker = line_checker::LineChecker::new();
+    line_checker.add_rule(VowelRule::with_class(
+        3,
+        CharClass::new("AEIOUÄÖÜ").ignoring_case(),
+    ));
+
+    assert!(line_checker.check("ÄöU"));
+    assert!(line_checker.check("aei"));
+    assert!(!line_checker.check("xyzÄ"));
+}
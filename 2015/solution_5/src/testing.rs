@@ -0,0 +1,66 @@
+use crate::helpers::Rule;
+use crate::line_checker::LineChecker;
+
+//Small xorshift generator so the randomized tests are reproducible without extra dependencies
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    //Uniform-ish in 0..bound
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn pick(&mut self, alphabet: &[char]) -> char {
+        alphabet[self.below(alphabet.len())]
+    }
+
+    pub fn string(&mut self, alphabet: &[char], max_length: usize) -> String {
+        let length = self.below(max_length + 1);
+        (0..length).map(|_| self.pick(alphabet)).collect()
+    }
+
+    //Random string with `feature` planted at a random char position, so rare features come up
+    pub fn planted(&mut self, alphabet: &[char], max_length: usize, feature: &str) -> String {
+        let mut chars: Vec<char> = self.string(alphabet, max_length).chars().collect();
+        let at = self.below(chars.len() + 1);
+        chars.splice(at..at, feature.chars());
+        chars.into_iter().collect()
+    }
+}
+
+//Checked before the random lines of every property
+const EDGE_CASES: [&str; 8] = ["", "a", "x", "ñ", "\u{aaaa}", "aa", "ññ", "xy"];
+
+//Panics with the first line where the rule and the reference predicate disagree
+pub fn assert_agrees<T: Rule + 'static>(
+    rule: T,
+    mut reference: impl FnMut(&str) -> bool,
+    mut generate: impl FnMut(&mut Rng) -> String,
+) {
+    let mut line_checker = LineChecker::new();
+    line_checker.add_rule(rule);
+    let mut rng = Rng::new(0x5eed);
+    let random_lines = (0..2000).map(|_| generate(&mut rng));
+
+    for line in EDGE_CASES.map(String::from).into_iter().chain(random_lines) {
+        let expected = reference(&line);
+        assert_eq!(
+            expected,
+            line_checker.check(&line),
+            "rule and reference disagree on {line:?}"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::Display;
+
+//What a rule found in a line. Offsets are char indexes into the line
+#[derive(Debug, PartialEq)]
+pub enum Evidence {
+    Nothing,
+    Vowels {
+        offsets: Vec<usize>,
+    },
+    RepeatedPairs {
+        //Pair text with the offsets of its earlier and repeated occurrence
+        repeats: Vec<(String, usize, usize)>,
+    },
+    ForbiddenSubstring {
+        substring: String,
+        offset: usize,
+    },
+    GapRepeat {
+        first: usize,
+        second: usize,
+    },
+    Run {
+        letter: String,
+        offset: usize,
+        length: usize,
+    },
+    Children(Vec<Verdict>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Verdict {
+    pub rule: String,
+    pub passed: bool,
+    pub evidence: Evidence,
+}
+
+impl Verdict {
+    pub fn new(rule: &str, passed: bool, evidence: Evidence) -> Self {
+        Self {
+            rule: rule.to_string(),
+            passed,
+            evidence,
+        }
+    }
+
+    fn write_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let outcome = if self.passed { "passed" } else { "failed" };
+        write!(
+            f,
+            "{:indent$}{}: {outcome}",
+            "",
+            self.rule,
+            indent = depth * 2
+        )?;
+
+        match &self.evidence {
+            Evidence::Nothing => writeln!(f),
+            Evidence::Vowels { offsets } => {
+                let offsets: Vec<String> = offsets.iter().map(usize::to_string).collect();
+                writeln!(f, " (vowels at [{}])", offsets.join(", "))
+            }
+            Evidence::RepeatedPairs { repeats } => {
+                let repeats: Vec<String> = repeats
+                    .iter()
+                    .map(|(pair, first, second)| format!("{pair:?} at {first} and {second}"))
+                    .collect();
+                writeln!(f, " (repeated pairs: [{}])", repeats.join(", "))
+            }
+            Evidence::ForbiddenSubstring { substring, offset } => {
+                writeln!(f, " (forbidden {substring:?} at {offset})")
+            }
+            Evidence::GapRepeat { first, second } => {
+                writeln!(f, " (repeat at {first} and {second})")
+            }
+            Evidence::Run {
+                letter,
+                offset,
+                length,
+            } => writeln!(f, " (run of {length} {letter:?} at {offset})"),
+            Evidence::Children(children) => {
+                writeln!(f)?;
+                for child in children {
+                    child.write_indented(f, depth + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+#[test]
+fn test_displays_nested_verdicts() {
+    let verdict = Verdict::new(
+        "all of",
+        false,
+        Evidence::Children(vec![
+            Verdict::new(
+                "vowels >= 3",
+                true,
+                Evidence::Vowels {
+                    offsets: vec![0, 2, 5],
+                },
+            ),
+            Verdict::new(
+                "forbidden",
+                false,
+                Evidence::ForbiddenSubstring {
+                    substring: String::from("xy"),
+                    offset: 3,
+                },
+            ),
+        ]),
+    );
+
+    assert_eq!(
+        "all of: failed\n  vowels >= 3: passed (vowels at [0, 2, 5])\n  forbidden: failed (forbidden \"xy\" at 3)\n",
+        verdict.to_string()
+    );
+}
@@ -0,0 +1,159 @@
+use crate::export::LightImage;
+use crate::{Action, ActionRectangleSelection, Errors, Light, LightBinary, LightGrid};
+
+use std::ops::RangeInclusive;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/*
+SquareMap for binary lights packed as one bit per light. A row range is changed a whole
+word at a time: OR to turn on, AND NOT to turn off and XOR to toggle.
+*/
+pub struct BitSquareMap {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    //Row after row, light x of a row is bit x % 64 of word x / 64
+    words: Vec<u64>,
+}
+
+impl BitSquareMap {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+}
+
+//Word operation with the same effect as the action on every bit of a LightBinary
+enum WordOperation {
+    Keep,
+    Set,
+    Clear,
+    Flip,
+}
+
+impl WordOperation {
+    fn of(action: Action) -> Self {
+        let mut off = LightBinary::new();
+        off.apply(action);
+        let mut on = LightBinary::new();
+        on.turn_on();
+        on.apply(action);
+
+        match (off.is_on(), on.is_on()) {
+            (false, true) => WordOperation::Keep,
+            (true, true) => WordOperation::Set,
+            (false, false) => WordOperation::Clear,
+            (true, false) => WordOperation::Flip,
+        }
+    }
+}
+
+//Bits of `word` that fall inside the range of lights
+fn mask(word: usize, lights: &RangeInclusive<usize>) -> u64 {
+    let first = word * WORD_BITS;
+    let low = lights.start().max(&first) - first;
+    let high = lights.end().min(&(first + WORD_BITS - 1)) - first;
+    (u64::MAX << low) & (u64::MAX >> (WORD_BITS - 1 - high))
+}
+
+impl LightGrid for BitSquareMap {
+    fn apply(&mut self, action: &ActionRectangleSelection) -> Result<(), Errors> {
+        action.fits(self.width, self.height)?;
+        let operation = WordOperation::of(action.action);
+
+        let lights = action.get_width();
+        let words = lights.start() / WORD_BITS..=lights.end() / WORD_BITS;
+
+        for y in action.get_height() {
+            let row = &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row];
+            for word in words.clone() {
+                let mask = mask(word, &lights);
+                match operation {
+                    WordOperation::Keep => {}
+                    WordOperation::Set => row[word] |= mask,
+                    WordOperation::Clear => row[word] &= !mask,
+                    WordOperation::Flip => row[word] ^= mask,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn num_lights(&self) -> Result<usize, Errors> {
+        Ok(self
+            .words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum())
+    }
+}
+
+impl LightImage for BitSquareMap {
+    fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn value_at(&self, x: usize, y: usize) -> usize {
+        let word = self.words[y * self.words_per_row + x / WORD_BITS];
+        (word >> (x % WORD_BITS) & 1) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::parse;
+    use crate::{count_lights, LightBinary, SquareMap};
+
+    #[test]
+    fn test_masks_partial_words() {
+        assert_eq!(0b1110, mask(0, &(1..=3)));
+        assert_eq!(u64::MAX, mask(1, &(0..=999)));
+        assert_eq!(1 << 63, mask(0, &(63..=64)));
+        assert_eq!(1, mask(1, &(63..=64)));
+    }
+
+    #[test]
+    fn test_matches_square_map() {
+        let actions = parse(&[
+            "turn on 0,0 through 999,999",
+            "toggle 0,0 through 999,0",
+            "turn off 499,499 through 500,500",
+            "toggle 3,7 through 600,12",
+            "turn off 63,0 through 64,999",
+            "toggle 127,5 through 128,5",
+            "turn on 998,998 through 999,999",
+        ]);
+
+        assert_eq!(
+            count_lights(SquareMap::<LightBinary>::new(1000, 1000), &actions),
+            count_lights(BitSquareMap::new(1000, 1000), &actions)
+        );
+    }
+
+    // Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_bit_map_against_square_map() {
+        use std::fs;
+        use std::time::Instant;
+
+        let text = fs::read_to_string("input.txt").unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let actions = parse(&lines);
+
+        let start = Instant::now();
+        let lights = count_lights(SquareMap::<LightBinary>::new(1000, 1000), &actions).unwrap();
+        println!("SquareMap: {lights} lights in {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let lights = count_lights(BitSquareMap::new(1000, 1000), &actions).unwrap();
+        println!("BitSquareMap: {lights} lights in {:?}", start.elapsed());
+    }
+}
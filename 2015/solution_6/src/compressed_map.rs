@@ -0,0 +1,200 @@
+use crate::export::LightImage;
+use crate::{ActionRectangleSelection, Errors, Light, LightGrid};
+
+use std::ops::{Range, RangeInclusive};
+
+/*
+Grid compressed over the edges of every instruction rectangle. Lights between two
+consecutive edges always receive the same instructions, so a single light stands in
+for the whole block and is weighted by its area when counting.
+*/
+pub struct CompressedMap<T> {
+    //Sorted block boundaries. Block i spans xs[i]..xs[i + 1]
+    xs: Vec<usize>,
+    ys: Vec<usize>,
+    blocks: Vec<Vec<T>>,
+}
+
+impl<T: Light + Clone + Default> CompressedMap<T> {
+    //Only the given instructions can be applied afterwards
+    pub fn new(actions: &[ActionRectangleSelection]) -> Self {
+        Self::filled(actions, T::default())
+    }
+}
+
+impl<T: Light + Clone> CompressedMap<T> {
+    pub fn filled(actions: &[ActionRectangleSelection], light: T) -> Self {
+        let xs = edges(actions.iter().map(|action| action.get_width()));
+        let ys = edges(actions.iter().map(|action| action.get_height()));
+        let blocks = vec![vec![light; xs.len().saturating_sub(1)]; ys.len().saturating_sub(1)];
+        Self { xs, ys, blocks }
+    }
+}
+
+//Start and end + 1 of every range, sorted and without duplicates. An end of usize::MAX
+//has no edge after it, applying its instruction fails instead
+fn edges(ranges: impl Iterator<Item = RangeInclusive<usize>>) -> Vec<usize> {
+    let mut edges: Vec<usize> = ranges
+        .flat_map(|range| [Some(*range.start()), range.end().checked_add(1)])
+        .flatten()
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+//Blocks covered by the range, which must start and end on edges
+fn block_range(edges: &[usize], range: RangeInclusive<usize>) -> Result<Range<usize>, Errors> {
+    let end = range
+        .end()
+        .checked_add(1)
+        .ok_or(Errors::CoordinateTooLarge)?;
+    let index = |edge: usize| {
+        edges
+            .binary_search(&edge)
+            .map_err(|_| Errors::NotInCompressedMap)
+    };
+    Ok(index(*range.start())?..index(end)?)
+}
+
+impl<T: Light + Clone> LightGrid for CompressedMap<T> {
+    //Never out of bounds, the map has no size of its own. Fails on instructions it was
+    //not built from, and on ones that reach the largest coordinate
+    fn apply(&mut self, action: &ActionRectangleSelection) -> Result<(), Errors> {
+        let columns = block_range(&self.xs, action.get_width())?;
+        let rows = block_range(&self.ys, action.get_height())?;
+        for row in &mut self.blocks[rows] {
+            for light in &mut row[columns.clone()] {
+                light.apply(action.action);
+            }
+        }
+
+        if T::STEPS {
+            self.blocks.iter_mut().flatten().for_each(Light::step);
+        }
+        Ok(())
+    }
+
+    //Blocks can be huge, so weighting them by area can overflow
+    fn num_lights(&self) -> Result<usize, Errors> {
+        let mut total: usize = 0;
+        for (y, row) in self.blocks.iter().enumerate() {
+            let height = self.ys[y + 1] - self.ys[y];
+            for (x, light) in row.iter().enumerate() {
+                let width = self.xs[x + 1] - self.xs[x];
+                total = light
+                    .get_value()
+                    .checked_mul(width)
+                    .and_then(|lights| lights.checked_mul(height))
+                    .and_then(|lights| total.checked_add(lights))
+                    .ok_or(Errors::TooManyLights)?;
+            }
+        }
+        Ok(total)
+    }
+}
+
+//Lights outside every instruction rectangle were never changed, so they are dark
+impl<T: Light> LightImage for CompressedMap<T> {
+    fn dimensions(&self) -> (usize, usize) {
+        (
+            self.xs.last().copied().unwrap_or(0),
+            self.ys.last().copied().unwrap_or(0),
+        )
+    }
+
+    fn value_at(&self, x: usize, y: usize) -> usize {
+        let block =
+            |edges: &[usize], at: usize| edges.partition_point(|edge| *edge <= at).checked_sub(1);
+        match (block(&self.xs, x), block(&self.ys, y)) {
+            (Some(column), Some(row)) => self.blocks[row][column].get_value(),
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::parse;
+    use crate::{count_lights, LightAmplifying, LightBinary, SquareMap};
+
+    #[test]
+    fn test_counts_overlapping_rectangles() {
+        let actions = parse(&[
+            "turn on 0,0 through 9,9",
+            "toggle 5,5 through 14,14",
+            "turn off 0,0 through 0,0",
+        ]);
+
+        assert_eq!(
+            Ok(99 - 25 + 75),
+            count_lights(CompressedMap::<LightBinary>::new(&actions), &actions)
+        );
+        assert_eq!(
+            Ok(99 + 100 * 2),
+            count_lights(CompressedMap::<LightAmplifying>::new(&actions), &actions)
+        );
+    }
+
+    #[test]
+    fn test_no_instructions() {
+        assert_eq!(
+            Ok(0),
+            count_lights(CompressedMap::<LightBinary>::new(&[]), &[])
+        );
+    }
+
+    #[test]
+    fn test_unknown_instruction_is_an_error() {
+        let actions = parse(&["turn on 0,0 through 9,9"]);
+        let mut map = CompressedMap::<LightBinary>::new(&actions);
+
+        assert_eq!(
+            Err(Errors::NotInCompressedMap),
+            map.apply(&parse(&["toggle 3,3 through 4,4"])[0])
+        );
+        assert_eq!(Ok(100), count_lights(map, &actions));
+    }
+
+    #[test]
+    fn test_largest_coordinate_is_an_error() {
+        let actions = parse(&["turn on 0,0 through 18446744073709551615,0"]);
+
+        assert_eq!(
+            Err(Errors::CoordinateTooLarge),
+            count_lights(CompressedMap::<LightBinary>::new(&actions), &actions)
+        );
+    }
+
+    #[test]
+    fn test_total_overflow_is_an_error() {
+        let actions = parse(&["toggle 0,0 through 4294967295,4294967295"]);
+
+        assert_eq!(
+            Err(Errors::TooManyLights),
+            count_lights(CompressedMap::<LightBinary>::new(&actions), &actions)
+        );
+    }
+
+    #[test]
+    fn test_matches_square_map() {
+        let actions = parse(&[
+            "turn on 0,0 through 999,999",
+            "toggle 0,0 through 999,0",
+            "turn off 499,499 through 500,500",
+            "toggle 3,7 through 600,12",
+            "turn off 10,0 through 10,999",
+            "turn on 998,998 through 999,999",
+        ]);
+
+        assert_eq!(
+            count_lights(SquareMap::<LightBinary>::new(1000, 1000), &actions),
+            count_lights(CompressedMap::<LightBinary>::new(&actions), &actions)
+        );
+        assert_eq!(
+            count_lights(SquareMap::<LightAmplifying>::new(1000, 1000), &actions),
+            count_lights(CompressedMap::<LightAmplifying>::new(&actions), &actions)
+        );
+    }
+}
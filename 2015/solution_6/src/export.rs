@@ -0,0 +1,181 @@
+use std::io::{self, Write};
+
+//Read access to the brightness of every light, so a grid can be drawn
+pub trait LightImage {
+    //Width and height in lights
+    fn dimensions(&self) -> (usize, usize);
+    fn value_at(&self, x: usize, y: usize) -> usize;
+}
+
+//Darkest to brightest, for the terminal preview
+const SHADES: &[u8] = b" .:-=+*#%@";
+
+fn max_value(image: &impl LightImage) -> usize {
+    let (width, height) = image.dimensions();
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| image.value_at(x, y))
+        .max()
+        .unwrap_or(0)
+}
+
+//Brightness scaled so that the brightest light of the image is 255
+fn gray_level(value: usize, max: usize) -> u8 {
+    if max == 0 {
+        return 0;
+    }
+    (value as u128 * 255 / max as u128) as u8
+}
+
+//Black through red and yellow to white
+fn heat(level: u8) -> [u8; 3] {
+    let level = level as usize * 3;
+    let channel = |offset: usize| level.saturating_sub(offset).min(255) as u8;
+    [channel(0), channel(255), channel(510)]
+}
+
+fn write_pixels<const N: usize>(
+    image: &impl LightImage,
+    out: &mut impl Write,
+    magic: &str,
+    pixel: impl Fn(u8) -> [u8; N],
+) -> io::Result<()> {
+    let (width, height) = image.dimensions();
+    let max = max_value(image);
+    write!(out, "{magic}\n{width} {height}\n255\n")?;
+
+    let mut row = Vec::with_capacity(width * N);
+    for y in 0..height {
+        row.clear();
+        for x in 0..width {
+            row.extend(pixel(gray_level(image.value_at(x, y), max)));
+        }
+        out.write_all(&row)?;
+    }
+    Ok(())
+}
+
+//Binary PGM where brighter lights are lighter grays
+pub fn write_pgm(image: &impl LightImage, out: &mut impl Write) -> io::Result<()> {
+    write_pixels(image, out, "P5", |level| [level])
+}
+
+//Binary PPM heat map of the same gray levels
+pub fn write_ppm(image: &impl LightImage, out: &mut impl Write) -> io::Result<()> {
+    write_pixels(image, out, "P6", heat)
+}
+
+/*
+Text picture at most `columns` wide and `rows` high. Every char shades the average
+brightness of the block of lights it covers, relative to the brightest light.
+*/
+pub fn preview(image: &impl LightImage, columns: usize, rows: usize) -> String {
+    let (width, height) = image.dimensions();
+    let columns = columns.min(width);
+    let rows = rows.min(height);
+    let max = max_value(image) as u128;
+
+    let mut text = String::new();
+    for row in 0..rows {
+        let ys = row * height / rows..(row + 1) * height / rows;
+        for column in 0..columns {
+            let xs = column * width / columns..(column + 1) * width / columns;
+            let lights = (xs.len() * ys.len()) as u128;
+            let total: u128 = ys
+                .clone()
+                .flat_map(|y| xs.clone().map(move |x| (x, y)))
+                .map(|(x, y)| image.value_at(x, y) as u128)
+                .sum();
+
+            let shade = if max == 0 {
+                0
+            } else {
+                total * (SHADES.len() - 1) as u128 / (lights * max)
+            };
+            text.push(SHADES[shade as usize] as char);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::parse;
+    use crate::{apply_all, BitSquareMap, CompressedMap, LightAmplifying, LightBinary, SquareMap};
+
+    fn map(width: usize, height: usize, lines: &[&str]) -> SquareMap<LightAmplifying> {
+        let mut map = SquareMap::new(width, height);
+        apply_all(&mut map, &parse(lines)).unwrap();
+        map
+    }
+
+    fn pgm(image: &impl LightImage) -> Vec<u8> {
+        let mut out = vec![];
+        write_pgm(image, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_pgm_scales_to_brightest_light() {
+        let map = map(3, 2, &["set 4 0,0 through 0,0", "set 2 1,1 through 2,1"]);
+        let mut expected = b"P5\n3 2\n255\n".to_vec();
+        expected.extend([255, 0, 0, 0, 127, 127]);
+        assert_eq!(expected, pgm(&map));
+    }
+
+    #[test]
+    fn test_ppm_heat_map() {
+        let map = map(2, 1, &["set 1 0,0 through 0,0"]);
+        let mut out = vec![];
+        write_ppm(&map, &mut out).unwrap();
+
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend([255, 255, 255, 0, 0, 0]);
+        assert_eq!(expected, out);
+        assert_eq!([255, 126, 0], heat(127));
+    }
+
+    #[test]
+    fn test_dark_map_is_black() {
+        let map = map(2, 2, &[]);
+        let out = pgm(&map);
+        assert_eq!(&[0; 4], &out[out.len() - 4..]);
+        assert_eq!("  \n  \n", preview(&map, 10, 10));
+    }
+
+    #[test]
+    fn test_preview_averages_blocks() {
+        //Left half fully lit, top right quarter half lit
+        let map = map(
+            4,
+            4,
+            &["turn on 0,0 through 1,3", "turn on 2,0 through 3,0"],
+        );
+
+        assert_eq!("@=\n@ \n", preview(&map, 2, 2));
+        assert_eq!("@@==\n@@  \n", preview(&map, 4, 2));
+    }
+
+    #[test]
+    fn test_every_engine_draws_the_same() {
+        let actions = parse(&[
+            "turn on 0,0 through 99,0",
+            "toggle 3,0 through 70,49",
+            "turn off 64,10 through 65,20",
+            "turn on 98,48 through 99,49",
+        ]);
+
+        let mut square = SquareMap::<LightBinary>::new(100, 50);
+        apply_all(&mut square, &actions).unwrap();
+        let mut bits = BitSquareMap::new(100, 50);
+        apply_all(&mut bits, &actions).unwrap();
+        let mut compressed = CompressedMap::<LightBinary>::new(&actions);
+        apply_all(&mut compressed, &actions).unwrap();
+
+        assert_eq!(pgm(&square), pgm(&bits));
+        assert_eq!(pgm(&square), pgm(&compressed));
+        assert_eq!(preview(&square, 20, 10), preview(&compressed, 20, 10));
+    }
+}
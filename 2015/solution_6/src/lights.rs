@@ -0,0 +1,302 @@
+use std::fmt::Display;
+
+//What an instruction does to every light in its rectangle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    TurnOn,
+    TurnOff,
+    Toggle,
+    Set(usize),
+    //"brighten 2" adds and "dim 3" subtracts
+    Add(isize),
+    Multiply(usize),
+    //Minimum and maximum, the minimum is never larger
+    Clamp(usize, usize),
+}
+
+pub trait Light {
+    //Lights that change on their own between instructions, see step
+    const STEPS: bool = false;
+
+    fn turn_on(&mut self);
+    fn turn_off(&mut self);
+    fn toggle(&mut self);
+    fn set(&mut self, value: usize);
+    fn get_value(&self) -> usize;
+
+    //The brightness actions set whatever they make of the current value
+    fn add(&mut self, amount: isize) {
+        self.set(self.get_value().saturating_add_signed(amount));
+    }
+
+    fn multiply(&mut self, factor: usize) {
+        self.set(self.get_value().saturating_mul(factor));
+    }
+
+    fn clamp(&mut self, min: usize, max: usize) {
+        self.set(self.get_value().clamp(min, max));
+    }
+
+    //Called on every light after every instruction, as long as STEPS is true
+    fn step(&mut self) {}
+
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::TurnOn => self.turn_on(),
+            Action::TurnOff => self.turn_off(),
+            Action::Toggle => self.toggle(),
+            Action::Set(value) => self.set(value),
+            Action::Add(amount) => self.add(amount),
+            Action::Multiply(factor) => self.multiply(factor),
+            Action::Clamp(min, max) => self.clamp(min, max),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum LightState {
+    Lit,
+    Unlit,
+}
+
+#[derive(Clone, Debug)]
+pub struct LightBinary {
+    state: LightState,
+}
+
+impl LightBinary {
+    pub fn new() -> Self {
+        Self {
+            state: LightState::Unlit,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.state == LightState::Lit
+    }
+}
+
+impl Light for LightBinary {
+    fn turn_on(&mut self) {
+        self.state = LightState::Lit;
+    }
+
+    fn turn_off(&mut self) {
+        self.state = LightState::Unlit;
+    }
+
+    fn toggle(&mut self) {
+        if self.state == LightState::Unlit {
+            self.state = LightState::Lit;
+        } else {
+            self.state = LightState::Unlit;
+        }
+    }
+
+    //Any brightness above 0 is on
+    fn set(&mut self, value: usize) {
+        if value > 0 {
+            self.turn_on();
+        } else {
+            self.turn_off();
+        }
+    }
+
+    fn get_value(&self) -> usize {
+        if self.is_on() {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for LightBinary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for LightBinary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.state {
+            LightState::Lit => write!(f, "1"),
+            LightState::Unlit => write!(f, "0"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LightAmplifying {
+    state: usize,
+}
+
+impl LightAmplifying {
+    pub fn new() -> Self {
+        Self { state: 0 }
+    }
+}
+
+//Saturates, set and multiply can already take a light to usize::MAX
+impl Light for LightAmplifying {
+    fn turn_on(&mut self) {
+        self.add(1);
+    }
+
+    fn turn_off(&mut self) {
+        self.add(-1);
+    }
+
+    fn toggle(&mut self) {
+        self.add(2);
+    }
+
+    fn set(&mut self, value: usize) {
+        self.state = value;
+    }
+
+    fn get_value(&self) -> usize {
+        self.state
+    }
+}
+
+impl Default for LightAmplifying {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for LightAmplifying {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.state)
+    }
+}
+
+//Like LightAmplifying, but never brighter than its cap
+#[derive(Clone, Debug)]
+pub struct LightCapped {
+    state: usize,
+    cap: usize,
+}
+
+impl LightCapped {
+    pub fn new(cap: usize) -> Self {
+        Self { state: 0, cap }
+    }
+}
+
+impl Light for LightCapped {
+    fn turn_on(&mut self) {
+        self.add(1);
+    }
+
+    fn turn_off(&mut self) {
+        self.add(-1);
+    }
+
+    fn toggle(&mut self) {
+        self.add(2);
+    }
+
+    fn set(&mut self, value: usize) {
+        self.state = value.min(self.cap);
+    }
+
+    fn get_value(&self) -> usize {
+        self.state
+    }
+}
+
+//Like LightAmplifying, but loses `decay` brightness after every instruction
+#[derive(Clone, Debug)]
+pub struct LightDecaying {
+    state: usize,
+    decay: usize,
+}
+
+impl LightDecaying {
+    pub fn new(decay: usize) -> Self {
+        Self { state: 0, decay }
+    }
+}
+
+impl Light for LightDecaying {
+    const STEPS: bool = true;
+
+    fn turn_on(&mut self) {
+        self.add(1);
+    }
+
+    fn turn_off(&mut self) {
+        self.add(-1);
+    }
+
+    fn toggle(&mut self) {
+        self.add(2);
+    }
+
+    fn set(&mut self, value: usize) {
+        self.state = value;
+    }
+
+    fn get_value(&self) -> usize {
+        self.state
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.saturating_sub(self.decay);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply_all<T: Light>(mut light: T, actions: &[Action]) -> usize {
+        for action in actions {
+            light.apply(*action);
+            if T::STEPS {
+                light.step();
+            }
+        }
+        light.get_value()
+    }
+
+    #[test]
+    fn test_brightness_actions() {
+        let actions = [Action::Set(5), Action::Add(-2), Action::Multiply(4)];
+        assert_eq!(12, apply_all(LightAmplifying::new(), &actions));
+        //Dimming a light that is on turns it off
+        assert_eq!(0, apply_all(LightBinary::new(), &actions));
+
+        let actions = [Action::Add(3), Action::Clamp(5, 8), Action::Multiply(2)];
+        assert_eq!(10, apply_all(LightAmplifying::new(), &actions));
+        assert_eq!(1, apply_all(LightBinary::new(), &actions));
+    }
+
+    #[test]
+    fn test_amplifying_light_saturates() {
+        let actions = [Action::Set(usize::MAX), Action::TurnOn, Action::Toggle];
+        assert_eq!(usize::MAX, apply_all(LightAmplifying::new(), &actions));
+
+        let actions = [Action::TurnOff, Action::Toggle, Action::TurnOff];
+        assert_eq!(1, apply_all(LightAmplifying::new(), &actions));
+    }
+
+    #[test]
+    fn test_capped_light() {
+        let actions = [Action::Toggle, Action::Toggle, Action::TurnOn];
+        assert_eq!(4, apply_all(LightCapped::new(4), &actions));
+
+        let actions = [Action::Toggle, Action::Toggle, Action::TurnOff];
+        assert_eq!(3, apply_all(LightCapped::new(4), &actions));
+    }
+
+    #[test]
+    fn test_decaying_light() {
+        let actions = [Action::Set(5), Action::TurnOn, Action::Toggle];
+        //5 - 2, 3 + 1 - 2, 2 + 2 - 2
+        assert_eq!(2, apply_all(LightDecaying::new(2), &actions));
+        assert_eq!(0, apply_all(LightDecaying::new(10), &actions));
+    }
+}
@@ -1,138 +1,129 @@
-use regex::Regex;
 use std::default::Default;
+use std::env;
 use std::fmt::Display;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
 use std::ops::RangeInclusive;
-use std::str::FromStr;
+
+mod bit_map;
+mod compressed_map;
+mod export;
+mod lights;
+mod parser;
+
+use bit_map::BitSquareMap;
+use compressed_map::CompressedMap;
+use export::LightImage;
+use lights::{Action, Light, LightAmplifying, LightBinary, LightCapped, LightDecaying};
+use parser::InstructionParser;
 
 //Map allocated on heap via vec as a 1000x1000 on an array will exceed the stack memory limit.
 const SQUARE_SIDE: usize = 1000;
+//Maps that keep every light are limited to 25 times the puzzle's, about 200MB for part two
+const MAX_LIGHTS: usize = 5000 * 5000;
+//A 1000x1000 map in blocks of 10x20 lights, as terminal chars are about twice as high as wide
+const PREVIEW_SIZE: (usize, usize) = (100, 50);
 
+#[derive(Debug, PartialEq)]
 enum Errors {
     OriginLargetDestination,
+    OutOfBounds { width: usize, height: usize },
+    MapTooLarge,
+    //An end of usize::MAX, which has no coordinate after it
+    CoordinateTooLarge,
+    //The count or total brightness does not fit in a usize
+    TooManyLights,
+    //Compressed maps only take the instructions they were built from
+    NotInCompressedMap,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-enum LightState {
-    Lit,
-    Unlit,
-}
-
-trait Light {
-    fn turn_on(&mut self);
-    fn turn_off(&mut self);
-    fn toggle(&mut self);
-    fn get_value(&self) -> usize;
-}
-
-#[derive(Clone, Debug)]
-struct LightBinary {
-    state: LightState,
-}
-
-impl LightBinary {
-    fn new() -> Self {
-        Self {
-            state: LightState::Unlit,
+impl Display for Errors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Errors::OriginLargetDestination => {
+                write!(
+                    f,
+                    "Origin coordinate cannot be larger than destination coordinate"
+                )
+            }
+            Errors::OutOfBounds { width, height } => {
+                write!(f, "Instruction reaches outside the {width}x{height} map")
+            }
+            Errors::MapTooLarge => {
+                write!(f, "Map would need more than {MAX_LIGHTS} lights")
+            }
+            Errors::CoordinateTooLarge => {
+                write!(f, "Instruction reaches the largest possible coordinate")
+            }
+            Errors::TooManyLights => write!(f, "Total is too large to count"),
+            Errors::NotInCompressedMap => {
+                write!(f, "Instruction was not part of the compressed map")
+            }
         }
     }
-
-    fn is_on(&self) -> bool {
-        self.state == LightState::Lit
-    }
 }
 
-impl Light for LightBinary {
-    fn turn_on(&mut self) {
-        self.state = LightState::Lit;
-    }
-
-    fn turn_off(&mut self) {
-        self.state = LightState::Unlit;
-    }
-
-    fn toggle(&mut self) {
-        if self.state == LightState::Unlit {
-            self.state = LightState::Lit;
-        } else {
-            self.state = LightState::Unlit;
-        }
-    }
-
-    fn get_value(&self) -> usize {
-        if self.state == LightState::Lit {
-            1
-        } else {
-            0
-        }
-    }
+//Anything instructions can be applied to, so the engines can be swapped
+trait LightGrid {
+    fn apply(&mut self, action: &ActionRectangleSelection) -> Result<(), Errors>;
+    //Lights on, or their total brightness
+    fn num_lights(&self) -> Result<usize, Errors>;
 }
 
-impl Default for LightBinary {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-#[derive(Clone, Debug, Default)]
-struct LightAmplifying {
-    state: usize,
+struct SquareMap<T> {
+    fields: Vec<Vec<T>>,
 }
 
-impl LightAmplifying {
-    fn new() -> Self {
-        Self { state: 0 }
+impl<T: Light + Clone + Default> SquareMap<T> {
+    //Despite the name, any rectangle of lights works
+    fn new(width: usize, height: usize) -> Self {
+        Self::filled(width, height, T::default())
     }
 }
 
-impl Light for LightAmplifying {
-    fn turn_on(&mut self) {
-        self.state += 1;
-    }
-
-    fn turn_off(&mut self) {
-        if self.state > 0 {
-            self.state -= 1;
+impl<T: Light + Clone> SquareMap<T> {
+    //For light models that need parameters, like the cap of LightCapped
+    fn filled(width: usize, height: usize, light: T) -> Self {
+        Self {
+            fields: vec![vec![light; width]; height],
         }
     }
-
-    fn toggle(&mut self) {
-        self.state += 2;
-    }
-
-    fn get_value(&self) -> usize {
-        self.state
-    }
-}
-
-struct SquareMap<T> {
-    fields: Vec<Vec<T>>,
 }
 
-impl<T: Light + Clone + Default> SquareMap<T> {
-    fn new(side: usize) -> Self {
-        Self {
-            fields: vec![vec![T::default(); side]; side],
-        }
-    }
+impl<T: Light + Clone> LightGrid for SquareMap<T> {
+    fn apply(&mut self, action: &ActionRectangleSelection) -> Result<(), Errors> {
+        let width = self.fields.first().map_or(0, Vec::len);
+        action.fits(width, self.fields.len())?;
 
-    fn apply(&mut self, action: ActionRectangleSelection) {
         for row in &mut self.fields[action.get_height()] {
             for light in &mut row[action.get_width()] {
-                match action.action {
-                    Action::TurnOn => light.turn_on(),
-                    Action::TurnOff => light.turn_off(),
-                    Action::Toggle => light.toggle(),
-                }
+                light.apply(action.action);
             }
         }
+
+        if T::STEPS {
+            self.fields.iter_mut().flatten().for_each(Light::step);
+        }
+        Ok(())
     }
 
-    fn num_lights(&self) -> usize {
+    //Brightness has no upper bound, so the total can overflow
+    fn num_lights(&self) -> Result<usize, Errors> {
         self.fields
             .iter()
             .flatten()
-            .map(|light| light.get_value())
-            .sum()
+            .try_fold(0usize, |total, light| total.checked_add(light.get_value()))
+            .ok_or(Errors::TooManyLights)
+    }
+}
+
+impl<T: Light> LightImage for SquareMap<T> {
+    fn dimensions(&self) -> (usize, usize) {
+        (self.fields.first().map_or(0, Vec::len), self.fields.len())
+    }
+
+    fn value_at(&self, x: usize, y: usize) -> usize {
+        self.fields[y][x].get_value()
     }
 }
 
@@ -148,22 +139,6 @@ impl<T: Display> Display for SquareMap<T> {
     }
 }
 
-impl Display for LightBinary {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.state {
-            LightState::Lit => write!(f, "1"),
-            LightState::Unlit => write!(f, "0"),
-        }
-    }
-}
-
-#[derive(Debug)]
-enum Action {
-    TurnOn,
-    TurnOff,
-    Toggle,
-}
-
 #[derive(Debug)]
 struct Coordinate {
     x: usize,
@@ -176,23 +151,35 @@ impl Coordinate {
     }
 }
 
-impl TryFrom<&str> for Coordinate {
-    type Error = &'static str;
-
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let mut coords = s.split(',');
-        let x = coords.next().unwrap().parse::<usize>().unwrap();
-        let y = coords.next().unwrap().parse::<usize>().unwrap();
-        Ok(Coordinate::new(x, y))
-    }
-}
-
 #[derive(Debug)]
 struct SequentialCoordinates {
     origin: Coordinate,
     destination: Coordinate,
 }
 
+//How the two corners of a `through` range may be given
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CornerOrder {
+    //Origin must be the top left corner, as in the puzzle
+    Strict,
+    //Any two opposite corners, in either order
+    Any,
+}
+
+impl SequentialCoordinates {
+    fn from_corners(corners: (Coordinate, Coordinate), order: CornerOrder) -> Result<Self, Errors> {
+        if order == CornerOrder::Strict {
+            return Self::try_from(corners);
+        }
+
+        let (first, second) = corners;
+        Ok(Self {
+            origin: Coordinate::new(first.x.min(second.x), first.y.min(second.y)),
+            destination: Coordinate::new(first.x.max(second.x), first.y.max(second.y)),
+        })
+    }
+}
+
 impl TryFrom<(Coordinate, Coordinate)> for SequentialCoordinates {
     type Error = Errors;
 
@@ -230,44 +217,252 @@ impl ActionRectangleSelection {
     fn get_height(&self) -> RangeInclusive<usize> {
         self.coordinates.origin.y..=self.coordinates.destination.y
     }
+
+    fn fits(&self, width: usize, height: usize) -> Result<(), Errors> {
+        let destination = &self.coordinates.destination;
+        if destination.x >= width || destination.y >= height {
+            return Err(Errors::OutOfBounds { width, height });
+        }
+        Ok(())
+    }
 }
 
-impl FromStr for ActionRectangleSelection {
-    type Err = &'static str;
+fn apply_all<G: LightGrid>(
+    grid: &mut G,
+    actions: &[ActionRectangleSelection],
+) -> Result<(), Errors> {
+    for action in actions {
+        grid.apply(action)?;
+    }
+    Ok(())
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        //turn on 0,0 through 999,999
-        let regex = Regex::new(r"(.+)\s(\d+,\d+) through (\d+,\d+)").unwrap();
+fn count_lights<G: LightGrid>(
+    mut grid: G,
+    actions: &[ActionRectangleSelection],
+) -> Result<usize, Errors> {
+    apply_all(&mut grid, actions)?;
+    grid.num_lights()
+}
 
-        let Some(captures) = regex.captures(s) else {
-            return Err("No matches in input string");
-        };
+//Smallest width and height that fit every instruction
+fn bounds(actions: &[ActionRectangleSelection]) -> Result<(usize, usize), Errors> {
+    let (mut width, mut height) = (0, 0);
+    for action in actions {
+        let size = |range: RangeInclusive<usize>| range.end().checked_add(1);
+        width = width.max(size(action.get_width()).ok_or(Errors::CoordinateTooLarge)?);
+        height = height.max(size(action.get_height()).ok_or(Errors::CoordinateTooLarge)?);
+    }
+    Ok((width, height))
+}
+
+//Maps that keep every light are only built when they fit in memory
+fn check_lights((width, height): (usize, usize)) -> Result<(), Errors> {
+    match width.checked_mul(height) {
+        Some(lights) if lights <= MAX_LIGHTS => Ok(()),
+        _ => Err(Errors::MapTooLarge),
+    }
+}
+
+//The compressed map has no size of its own, but must report the same errors
+fn check_fits(
+    actions: &[ActionRectangleSelection],
+    (width, height): (usize, usize),
+) -> Result<(), Errors> {
+    actions
+        .iter()
+        .try_for_each(|action| action.fits(width, height))
+}
+
+//"1000x500" into a width and a height
+fn parse_size(size: &str) -> Option<(usize, usize)> {
+    let (width, height) = size.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
 
-        if captures.len() != 4 {
-            return Err("Input does not have all the needed information");
+//Light models that can be picked with --light, besides the ones of the two parts
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LightModel {
+    Capped(usize),
+    Decaying(usize),
+}
+
+//"capped:10" or "decaying:1"
+fn parse_light_model(model: &str) -> Option<LightModel> {
+    let (name, parameter) = model.split_once(':')?;
+    let parameter = parameter.parse().ok()?;
+    match name {
+        "capped" => Some(LightModel::Capped(parameter)),
+        "decaying" => Some(LightModel::Decaying(parameter)),
+        _ => None,
+    }
+}
+
+fn run_light<T: Light + Clone>(
+    light: T,
+    actions: &[ActionRectangleSelection],
+    compressed: bool,
+    (width, height): (usize, usize),
+) -> Result<usize, Errors> {
+    if compressed {
+        check_fits(actions, (width, height))?;
+        count_lights(CompressedMap::filled(actions, light), actions)
+    } else {
+        check_lights((width, height))?;
+        count_lights(SquareMap::filled(width, height, light), actions)
+    }
+}
+
+//Total brightness with one of the extra light models
+fn run_model(
+    model: LightModel,
+    actions: &[ActionRectangleSelection],
+    compressed: bool,
+    size: (usize, usize),
+) -> Result<usize, Errors> {
+    match model {
+        LightModel::Capped(cap) => run_light(LightCapped::new(cap), actions, compressed, size),
+        LightModel::Decaying(decay) => {
+            run_light(LightDecaying::new(decay), actions, compressed, size)
         }
-        //Check for action
-        let action = match captures[1].as_ref() {
-            "turn on" => Action::TurnOn,
-            "turn off" => Action::TurnOff,
-            "toggle" => Action::Toggle,
-            _ => return Err("Invalid action specified"),
-        };
+    }
+}
+
+//Lights on after part one and total brightness after part two
+fn run_parts(
+    actions: &[ActionRectangleSelection],
+    compressed: bool,
+    (width, height): (usize, usize),
+) -> Result<(usize, usize), Errors> {
+    if compressed {
+        check_fits(actions, (width, height))?;
+        return Ok((
+            count_lights(CompressedMap::<LightBinary>::new(actions), actions)?,
+            count_lights(CompressedMap::<LightAmplifying>::new(actions), actions)?,
+        ));
+    }
+
+    check_lights((width, height))?;
+    Ok((
+        count_lights(BitSquareMap::new(width, height), actions)?,
+        count_lights(SquareMap::<LightAmplifying>::new(width, height), actions)?,
+    ))
+}
 
-        let origin = Coordinate::try_from(&captures[2])?;
+//Pictures of the final brightness asked for on the command line
+#[derive(Default)]
+struct Exports {
+    pgm: Option<String>,
+    ppm: Option<String>,
+    preview: bool,
+}
+
+fn write_image(
+    path: &str,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write(&mut out)?;
+    out.flush()
+}
 
-        let dest = Coordinate::try_from(&captures[3])?;
+//Draws the full map of the light model, even when counting used another engine
+fn export_light<T: Light + Clone>(
+    light: T,
+    actions: &[ActionRectangleSelection],
+    (width, height): (usize, usize),
+    exports: &Exports,
+) {
+    if let Err(e) = check_lights((width, height)) {
+        eprintln!("{e}");
+        return;
+    }
+    let mut map = SquareMap::filled(width, height, light);
+    if let Err(e) = apply_all(&mut map, actions) {
+        eprintln!("{e}");
+        return;
+    }
 
-        //        let dest = Coordinate::new(9, 9);
-        match SequentialCoordinates::try_from((origin, dest)) {
-            Err(_) => Err("Origin coordinate cannot be larger than destination coordinate"),
-            Ok(coordinates) => Ok(Self::new(coordinates, action)),
+    if let Some(path) = &exports.pgm {
+        if let Err(e) = write_image(path, |out| export::write_pgm(&map, out)) {
+            eprintln!("Error writing {path}. Error {e}");
         }
     }
+    if let Some(path) = &exports.ppm {
+        if let Err(e) = write_image(path, |out| export::write_ppm(&map, out)) {
+            eprintln!("Error writing {path}. Error {e}");
+        }
+    }
+
+    if exports.preview {
+        let (columns, rows) = PREVIEW_SIZE;
+        print!("{}", export::preview(&map, columns, rows));
+    }
 }
 
+// Usage: solution_6 [--compressed] [--size WIDTHxHEIGHT | --fit] [--skip-invalid]
+//                   [--any-corner-order] [--light capped:CAP | decaying:DECAY]
+//                   [--pgm FILE] [--ppm FILE] [--preview]
+// With --compressed, the grid is coordinate compressed over the instruction rectangles
+// instead of keeping every light, so its size doesn't matter. Otherwise part one packs
+// the lights into bits.
+// The map is 1000x1000 unless --size is given, or --fit makes it just big enough for
+// the instructions. Without --compressed it can hold at most 25 million lights.
+// Every invalid line is reported. Nothing is run if there are any, unless --skip-invalid
+// is given to run the valid ones.
+// With --any-corner-order, "through" ranges may go from any corner to the opposite one.
+// With --light, only the total brightness of lights capped at CAP, or losing DECAY
+// brightness after every instruction, is printed.
+// Besides turn on/off and toggle, instructions can set 5, brighten 2, dim 3, multiply 2
+// or clamp 1 8 the brightness of their lights.
+// --pgm writes the final brightness as a gray image, --ppm as a heat map and --preview
+// prints it downsampled to the terminal. The brightest light is white in all of them. It
+// is the brightness of part two, or of the --light model.
 fn main() {
-    let mut map: SquareMap<LightAmplifying> = SquareMap::new(SQUARE_SIDE);
+    let mut compressed = false;
+    let mut skip_invalid = false;
+    let mut order = CornerOrder::Strict;
+    let mut model = None;
+    let mut exports = Exports::default();
+    //None fits the map to the instructions
+    let mut size = Some((SQUARE_SIDE, SQUARE_SIDE));
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compressed" => compressed = true,
+            "--fit" => size = None,
+            "--skip-invalid" => skip_invalid = true,
+            "--any-corner-order" => order = CornerOrder::Any,
+            "--preview" => exports.preview = true,
+            "--pgm" | "--ppm" => match args.next() {
+                Some(path) if arg == "--pgm" => exports.pgm = Some(path),
+                Some(path) => exports.ppm = Some(path),
+                None => {
+                    eprintln!("{arg} needs a file");
+                    return;
+                }
+            },
+            "--light" => match args.next().as_deref().and_then(parse_light_model) {
+                Some(picked) => model = Some(picked),
+                None => {
+                    eprintln!("--light needs capped:CAP or decaying:DECAY");
+                    return;
+                }
+            },
+            "--size" => match args.next().as_deref().and_then(parse_size) {
+                Some(fixed) => size = Some(fixed),
+                None => {
+                    eprintln!("--size needs WIDTHxHEIGHT");
+                    return;
+                }
+            },
+            _ => {
+                eprintln!("Unknown argument {arg}");
+                return;
+            }
+        }
+    }
 
     let file_path = "input.txt";
     let text = match fs::read_to_string(file_path) {
@@ -277,20 +472,193 @@ fn main() {
         }
     };
 
-    //Read input char by char
-    for line in text.lines() {
-        let action: ActionRectangleSelection = match ActionRectangleSelection::from_str(line) {
-            Ok(action) => action,
+    let parser = InstructionParser::new(order);
+    let mut actions = vec![];
+    let mut invalid = 0;
+    for result in parser.parse_lines(&text) {
+        match result {
+            Ok(action) => actions.push(action),
             Err(e) => {
                 eprintln!("{e}");
-                return;
+                invalid += 1;
             }
+        }
+    }
+
+    if invalid > 0 {
+        if !skip_invalid {
+            eprintln!("{invalid} invalid lines, nothing was run");
+            return;
+        }
+        eprintln!("Skipped {invalid} invalid lines");
+    }
+
+    let size = match size.map_or_else(|| bounds(&actions), Ok) {
+        Ok(size) => size,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        }
+    };
+    let counted = match model {
+        Some(model) => run_model(model, &actions, compressed, size)
+            .map(|brightness| println!("Total brightness: {brightness}")),
+        None => run_parts(&actions, compressed, size).map(|(binary, amplifying)| {
+            println!("Lights on: {binary}");
+            println!("Total brightness: {amplifying}");
+        }),
+    };
+    if let Err(e) = counted {
+        eprintln!("{e}");
+        return;
+    }
+
+    if exports.pgm.is_none() && exports.ppm.is_none() && !exports.preview {
+        return;
+    }
+    match model {
+        Some(LightModel::Capped(cap)) => {
+            export_light(LightCapped::new(cap), &actions, size, &exports)
+        }
+        Some(LightModel::Decaying(decay)) => {
+            export_light(LightDecaying::new(decay), &actions, size, &exports)
+        }
+        None => export_light(LightAmplifying::new(), &actions, size, &exports),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parser::parse;
+
+    #[test]
+    fn test_rectangular_map() {
+        let actions = parse(&["turn on 0,0 through 9,2", "toggle 5,0 through 9,0"]);
+
+        assert_eq!(
+            Ok(25),
+            count_lights(SquareMap::<LightBinary>::new(10, 3), &actions)
+        );
+        assert_eq!(Ok(25), count_lights(BitSquareMap::new(10, 3), &actions));
+    }
+
+    #[test]
+    fn test_out_of_bounds_is_an_error() {
+        let actions = parse(&["turn on 0,0 through 10,2"]);
+        let error = Err(Errors::OutOfBounds {
+            width: 10,
+            height: 3,
+        });
+
+        assert_eq!(
+            error,
+            count_lights(SquareMap::<LightBinary>::new(10, 3), &actions)
+        );
+        assert_eq!(error, count_lights(BitSquareMap::new(10, 3), &actions));
+    }
+
+    #[test]
+    fn test_compressed_map_honours_size() {
+        let actions = parse(&["turn on 0,0 through 5,5"]);
+        let error = Errors::OutOfBounds {
+            width: 3,
+            height: 3,
         };
-        map.apply(action);
+
+        for compressed in [false, true] {
+            assert_eq!(error, run_parts(&actions, compressed, (3, 3)).unwrap_err());
+            assert_eq!(
+                error,
+                run_model(LightModel::Capped(1), &actions, compressed, (3, 3)).unwrap_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fit_overflowing_coordinate() {
+        let actions = parse(&["turn on 0,0 through 18446744073709551615,0"]);
+        assert_eq!(Err(Errors::CoordinateTooLarge), bounds(&actions));
     }
 
-    // println!("{action:?}");
+    #[test]
+    fn test_fit_map_too_large() {
+        let actions = parse(&["turn on 0,0 through 999999999,999999999"]);
+        let size = bounds(&actions).unwrap();
+
+        assert_eq!(Err(Errors::MapTooLarge), run_parts(&actions, false, size));
+        assert_eq!(
+            Err(Errors::MapTooLarge),
+            run_light(LightBinary::new(), &actions, false, size)
+        );
+        //The compressed map only keeps the instruction rectangles
+        assert_eq!(
+            Ok((10usize.pow(18), 10usize.pow(18))),
+            run_parts(&actions, true, size)
+        );
+    }
+
+    #[test]
+    fn test_light_models_on_every_engine() {
+        let actions = parse(&[
+            "set 5 0,0 through 3,3",
+            "brighten 2 2,2 through 5,5",
+            "dim 10 0,0 through 0,0",
+            "multiply 3 0,0 through 5,0",
+        ]);
+
+        for compressed in [false, true] {
+            //11 lights at 5, 4 at 7 and 12 at 2, then 3 of the 5s tripled
+            assert_eq!(
+                Ok(11 * 5 + 4 * 7 + 12 * 2 + 3 * 5 * 2),
+                run_light(LightAmplifying::new(), &actions, compressed, (6, 6))
+            );
+            //15 lights capped at 3 and 12 at 2
+            assert_eq!(
+                Ok(15 * 3 + 12 * 2),
+                run_model(LightModel::Capped(3), &actions, compressed, (6, 6))
+            );
+        }
+
+        assert_eq!(
+            Ok(16 + 12 - 1),
+            count_lights(BitSquareMap::new(6, 6), &actions)
+        );
+        assert_eq!(
+            Some(LightModel::Decaying(2)),
+            parse_light_model("decaying:2")
+        );
+        assert_eq!(None, parse_light_model("sparkling:2"));
+    }
 
-    println!("COUNT: {}", map.num_lights());
-    // println!("{map}");
+    #[test]
+    fn test_brightness_overflow_is_an_error() {
+        let actions = parse(&[
+            "set 18446744073709551615 0,0 through 1,1",
+            "turn on 0,0 through 0,0",
+        ]);
+
+        for compressed in [false, true] {
+            assert_eq!(
+                Err(Errors::TooManyLights),
+                run_light(LightAmplifying::new(), &actions, compressed, (2, 2))
+            );
+        }
+        //A single light at the maximum still counts
+        let actions = parse(&["set 18446744073709551615 0,0 through 0,0"]);
+        assert_eq!(
+            Ok(usize::MAX),
+            run_light(LightAmplifying::new(), &actions, false, (2, 2))
+        );
+    }
+
+    #[test]
+    fn test_fits_map_to_instructions() {
+        let actions = parse(&["turn on 0,0 through 9,2", "toggle 5,4 through 7,4"]);
+
+        assert_eq!(Ok((10, 5)), bounds(&actions));
+        assert_eq!(Ok((0, 0)), bounds(&[]));
+        assert_eq!(Some((1000, 500)), parse_size("1000x500"));
+        assert_eq!(None, parse_size("1000"));
+    }
 }
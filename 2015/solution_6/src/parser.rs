@@ -0,0 +1,362 @@
+use crate::{
+    Action, ActionRectangleSelection, Coordinate, CornerOrder, Errors, SequentialCoordinates,
+};
+
+use regex::Regex;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+//Shared by every from_str call, so that the regex is only compiled once
+static STRICT_PARSER: LazyLock<InstructionParser> =
+    LazyLock::new(|| InstructionParser::new(CornerOrder::Strict));
+
+//Which of the two corners of an instruction a parse error is in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Corner {
+    Origin,
+    Destination,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseReason {
+    NoMatch,
+    UnknownAction(String),
+    MissingNumber,
+    //Not a number, or too large for a usize. Coordinates outside the map are only
+    //found when the instruction is applied
+    InvalidNumber(String),
+    //Number of a brightness action like "dim 3"
+    InvalidAmount(String),
+    EmptyClamp,
+    Coordinates(Errors),
+}
+
+//Where and why an instruction line could not be parsed. Line and column start at 1.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub corner: Option<Corner>,
+    pub reason: ParseReason,
+}
+
+impl ParseError {
+    fn new(column: usize, reason: ParseReason) -> Self {
+        Self {
+            line: 1,
+            column,
+            corner: None,
+            reason,
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    //For errors found in a corner that starts `offset` chars into the line
+    fn in_corner(mut self, corner: Corner, offset: usize) -> Self {
+        self.corner = Some(corner);
+        self.column += offset;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}, column {}: ", self.line, self.column)?;
+        match self.corner {
+            Some(Corner::Origin) => write!(f, "origin ")?,
+            Some(Corner::Destination) => write!(f, "destination ")?,
+            None => {}
+        }
+
+        match &self.reason {
+            ParseReason::NoMatch => write!(f, "expected ACTION X,Y through X,Y"),
+            ParseReason::UnknownAction(action) => write!(f, "unknown action {action:?}"),
+            ParseReason::MissingNumber => write!(f, "coordinate needs X,Y"),
+            ParseReason::InvalidNumber(number) => write!(f, "coordinate {number} is not valid"),
+            ParseReason::InvalidAmount(amount) => write!(f, "amount {amount} is not valid"),
+            ParseReason::EmptyClamp => write!(f, "clamp minimum is larger than its maximum"),
+            ParseReason::Coordinates(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Coordinate {
+    type Error = ParseError;
+
+    //Columns of the errors are relative to the start of the coordinate
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let Some((x, y)) = s.split_once(',') else {
+            return Err(ParseError::new(1, ParseReason::MissingNumber));
+        };
+
+        let number = |text: &str, column: usize| {
+            text.parse::<usize>()
+                .map_err(|_| ParseError::new(column, ParseReason::InvalidNumber(text.to_string())))
+        };
+        Ok(Coordinate::new(number(x, 1)?, number(y, x.len() + 2)?))
+    }
+}
+
+impl FromStr for ActionRectangleSelection {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        STRICT_PARSER.parse(s)
+    }
+}
+
+/*
+Action part of an instruction line:
+
+    turn on | turn off | toggle
+    set 5 | brighten 2 | dim 3 | multiply 2 | clamp 1 8
+*/
+fn parse_action(text: &str) -> Result<Action, ParseReason> {
+    let amount = |word: &str| {
+        word.parse::<usize>()
+            .map_err(|_| ParseReason::InvalidAmount(word.to_string()))
+    };
+    let signed = |word: &str| {
+        word.parse::<isize>()
+            .ok()
+            .filter(|amount| *amount >= 0)
+            .ok_or_else(|| ParseReason::InvalidAmount(word.to_string()))
+    };
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    Ok(match words.as_slice() {
+        ["turn", "on"] => Action::TurnOn,
+        ["turn", "off"] => Action::TurnOff,
+        ["toggle"] => Action::Toggle,
+        ["set", value] => Action::Set(amount(value)?),
+        ["brighten", value] => Action::Add(signed(value)?),
+        ["dim", value] => Action::Add(-signed(value)?),
+        ["multiply", value] => Action::Multiply(amount(value)?),
+        ["clamp", min, max] => {
+            let (min, max) = (amount(min)?, amount(max)?);
+            if min > max {
+                return Err(ParseReason::EmptyClamp);
+            }
+            Action::Clamp(min, max)
+        }
+        _ => return Err(ParseReason::UnknownAction(text.to_string())),
+    })
+}
+
+//Parses instruction lines, compiling the regex once for all of them
+pub struct InstructionParser {
+    regex: Regex,
+    order: CornerOrder,
+}
+
+impl InstructionParser {
+    pub fn new(order: CornerOrder) -> Self {
+        Self {
+            //turn on 0,0 through 999,999
+            regex: Regex::new(r"(.+)\s(\d+,\d+) through (\d+,\d+)").unwrap(),
+            order,
+        }
+    }
+
+    //Errors are reported on line 1, callers parsing a file set the line with at_line
+    pub fn parse(&self, s: &str) -> Result<ActionRectangleSelection, ParseError> {
+        let Some(captures) = self.regex.captures(s) else {
+            return Err(ParseError::new(1, ParseReason::NoMatch));
+        };
+
+        let action = parse_action(&captures[1]).map_err(|reason| ParseError::new(1, reason))?;
+
+        let corner = |index: usize, corner: Corner| {
+            let text = captures.get(index).unwrap();
+            let offset = s[..text.start()].chars().count();
+            Coordinate::try_from(text.as_str()).map_err(|e| e.in_corner(corner, offset))
+        };
+        let origin = corner(2, Corner::Origin)?;
+        let dest = corner(3, Corner::Destination)?;
+
+        match SequentialCoordinates::from_corners((origin, dest), self.order) {
+            Err(e) => {
+                let column = s[..captures.get(2).unwrap().start()].chars().count() + 1;
+                Err(ParseError::new(column, ParseReason::Coordinates(e)))
+            }
+            Ok(coordinates) => Ok(ActionRectangleSelection::new(coordinates, action)),
+        }
+    }
+
+    //Every line of the text, with the line numbers set on the errors
+    pub fn parse_lines<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> impl Iterator<Item = Result<ActionRectangleSelection, ParseError>> + 'a {
+        text.lines()
+            .enumerate()
+            .map(|(index, line)| self.parse(line).map_err(|e| e.at_line(index + 1)))
+    }
+}
+
+//Instruction lines for tests, which must all be valid
+#[cfg(test)]
+pub fn parse(lines: &[&str]) -> Vec<ActionRectangleSelection> {
+    lines
+        .iter()
+        .map(|line| STRICT_PARSER.parse(line).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |line: &str| ActionRectangleSelection::from_str(line).unwrap_err();
+
+        assert_eq!(
+            ParseError {
+                line: 1,
+                column: 9,
+                corner: Some(Corner::Origin),
+                reason: ParseReason::InvalidNumber(String::from("1000000000000000000000")),
+            },
+            error("turn on 1000000000000000000000,0 through 5,5")
+        );
+        assert_eq!(
+            ParseError {
+                line: 1,
+                column: 23,
+                corner: Some(Corner::Destination),
+                reason: ParseReason::InvalidNumber(String::from("99999999999999999999")),
+            },
+            error("turn on 0,0 through 5,99999999999999999999")
+        );
+        assert_eq!(
+            ParseError::new(1, ParseReason::UnknownAction(String::from("turn up"))),
+            error("turn up 0,0 through 5,5")
+        );
+        assert_eq!(
+            ParseError::new(9, ParseReason::Coordinates(Errors::OriginLargetDestination)),
+            error("turn on 6,0 through 5,5")
+        );
+        assert_eq!(ParseReason::NoMatch, error("turn on 0,0 to 5,5").reason);
+    }
+
+    #[test]
+    fn test_normalizes_corners_in_any_order() {
+        let parse = |line: &str| {
+            InstructionParser::new(CornerOrder::Any)
+                .parse(line)
+                .unwrap()
+        };
+
+        for line in [
+            "toggle 1,2 through 5,7",
+            "toggle 5,7 through 1,2",
+            "toggle 5,2 through 1,7",
+            "toggle 1,7 through 5,2",
+        ] {
+            let action = parse(line);
+            assert_eq!(1..=5, action.get_width());
+            assert_eq!(2..=7, action.get_height());
+        }
+
+        let strict = InstructionParser::new(CornerOrder::Strict).parse("toggle 5,2 through 1,7");
+        assert_eq!(
+            ParseReason::Coordinates(Errors::OriginLargetDestination),
+            strict.unwrap_err().reason
+        );
+    }
+
+    #[test]
+    fn test_parse_error_message() {
+        let error = ActionRectangleSelection::from_str("toggle 0,0 through 5,99999999999999999999")
+            .unwrap_err()
+            .at_line(7);
+
+        assert_eq!(
+            "Line 7, column 22: destination coordinate 99999999999999999999 is not valid",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parses_brightness_actions() {
+        assert_eq!(Ok(Action::Set(5)), parse_action("set 5"));
+        assert_eq!(Ok(Action::Add(2)), parse_action("brighten 2"));
+        assert_eq!(Ok(Action::Add(-3)), parse_action("dim 3"));
+        assert_eq!(Ok(Action::Multiply(2)), parse_action("multiply 2"));
+        assert_eq!(Ok(Action::Clamp(1, 8)), parse_action("clamp 1 8"));
+
+        assert_eq!(
+            Err(ParseReason::InvalidAmount(String::from("-3"))),
+            parse_action("dim -3")
+        );
+        assert_eq!(Err(ParseReason::EmptyClamp), parse_action("clamp 8 1"));
+        assert_eq!(
+            Err(ParseReason::UnknownAction(String::from("set"))),
+            parse_action("set")
+        );
+
+        let parser = InstructionParser::new(CornerOrder::Strict);
+        let action = parser.parse("dim 3 0,0 through 2,2").unwrap();
+        assert_eq!(Action::Add(-3), action.action);
+    }
+
+    #[test]
+    fn test_parses_every_line() {
+        let parser = InstructionParser::new(CornerOrder::Strict);
+        let text = "turn on 0,0 through 1,1\nturn up 0,0 through 1,1\ntoggle 0,0 through 0,0";
+        let results: Vec<_> = parser.parse_lines(text).collect();
+
+        assert_eq!(3, results.len());
+        assert!(results[0].is_ok());
+        assert_eq!(2, results[1].as_ref().unwrap_err().line);
+        assert!(results[2].is_ok());
+    }
+
+    //100k random instructions, generated with a small LCG so the benchmark is repeatable
+    fn generated_instructions() -> String {
+        let mut state: u64 = 6;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+
+        let actions = ["turn on", "turn off", "toggle"];
+        let mut text = String::new();
+        for _ in 0..100_000 {
+            let action = actions[next(3) as usize];
+            let (x, y) = (next(1000), next(1000));
+            let (to_x, to_y) = (x + next(1000 - x), y + next(1000 - y));
+            text.push_str(&format!("{action} {x},{y} through {to_x},{to_y}\n"));
+        }
+        text
+    }
+
+    // Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_parser_against_from_str() {
+        use std::time::Instant;
+
+        let text = generated_instructions();
+
+        let start = Instant::now();
+        let parsed = text
+            .lines()
+            .filter(|line| ActionRectangleSelection::from_str(line).is_ok())
+            .count();
+        println!("from_str: {parsed} lines in {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let parser = InstructionParser::new(CornerOrder::Strict);
+        let parsed = parser.parse_lines(&text).filter(Result::is_ok).count();
+        println!("InstructionParser: {parsed} lines in {:?}", start.elapsed());
+    }
+}